@@ -75,7 +75,7 @@ fn generate_mcp_schemas(fds: &prost_types::FileDescriptorSet, out_dir: &std::pat
 
     for msg in &file_desc.message_type {
         let msg_name = msg.name.as_deref().unwrap_or_default();
-        if !msg_name.starts_with("Request") {
+        if !is_tool_request(msg_name) {
             continue;
         }
 
@@ -100,7 +100,7 @@ fn generate_mcp_schemas(fds: &prost_types::FileDescriptorSet, out_dir: &std::pat
 
     for msg in &file_desc.message_type {
         let msg_name = msg.name.as_deref().unwrap_or_default();
-        if !msg_name.starts_with("Request") {
+        if !is_tool_request(msg_name) {
             continue;
         }
         let fields: Vec<String> =
@@ -116,6 +116,13 @@ fn generate_mcp_schemas(fds: &prost_types::FileDescriptorSet, out_dir: &std::pat
     std::fs::write(out_dir.join("mcp_schemas.rs"), code).expect("failed to write mcp_schemas.rs");
 }
 
+/// Whether a message is the parameter type of a single request. `RequestToAUT` is the
+/// envelope of all requests and would expand into every other schema at once.
+#[cfg(feature = "mcp")]
+fn is_tool_request(msg_name: &str) -> bool {
+    msg_name.starts_with("Request") && msg_name != "RequestToAUT"
+}
+
 #[cfg(feature = "mcp")]
 fn message_to_json_schema(
     msg: &prost_types::DescriptorProto,
@@ -247,15 +247,15 @@ pub fn find_elements_by_id(
             .collect::<Vec<_>>())
     }
 
+    /// Grabs the window contents and encodes them as `image_mime_type` (PNG if empty).
+    /// If `crop_to` is set, the image is cropped to that element's bounds.
     pub fn take_snapshot(
         &self,
         window_index: ArenaIndex,
         image_mime_type: &str,
-    ) -> Result<Vec<u8>, String> {
-        let adapter = self.window_adapter(window_index)?;
-        let window = adapter.window();
-        let buffer =
-            window.take_snapshot().map_err(|e| format!("Error grabbing window screenshot: {e}"))?;
+        crop_to: Option<ArenaIndex>,
+        quality: Option<u8>,
+    ) -> Result<proto::TakeSnapshotResponse, String> {
         let format = if image_mime_type.is_empty() {
             image::ImageFormat::Png
         } else {
@@ -265,18 +265,39 @@ pub fn take_snapshot(
                 )
             })?
         };
-        let mut encoded: Vec<u8> = Vec::new();
-        let mut cursor = std::io::Cursor::new(&mut encoded);
-        image::write_buffer_with_format(
-            &mut cursor,
-            buffer.as_bytes(),
-            buffer.width(),
-            buffer.height(),
-            image::ExtendedColorType::Rgba8,
-            format,
-        )
-        .map_err(|e| format!("error encoding {image_mime_type} image after screenshot: {e}"))?;
-        Ok(encoded)
+        let image = self.capture_window(window_index, crop_to)?;
+        let (width, height) = image.dimensions();
+        let encoded = encode_image(image, format, quality)
+            .map_err(|e| format!("error encoding {image_mime_type} image after screenshot: {e}"))?;
+        Ok(proto::TakeSnapshotResponse { window_contents_as_encoded_image: encoded, width, height })
+    }
+
+    /// Grabs the window contents, cropped to the bounds of `crop_to` if set.
+    pub fn capture_window(
+        &self,
+        window_index: ArenaIndex,
+        crop_to: Option<ArenaIndex>,
+    ) -> Result<image::RgbaImage, String> {
+        let adapter = self.window_adapter(window_index)?;
+        let window = adapter.window();
+        let buffer =
+            window.take_snapshot().map_err(|e| format!("Error grabbing window screenshot: {e}"))?;
+        let mut image =
+            image::RgbaImage::from_raw(buffer.width(), buffer.height(), buffer.as_bytes().to_vec())
+                .ok_or_else(|| "window screenshot has an unexpected buffer size".to_string())?;
+        if let Some(element_index) = crop_to {
+            let element = self.element("take_snapshot", element_index)?;
+            let (x, y, width, height) = physical_crop_rect(
+                element.absolute_position(),
+                element.size(),
+                window.scale_factor(),
+                image.width(),
+                image.height(),
+            )
+            .ok_or_else(|| "element lies outside the window".to_string())?;
+            image = image::imageops::crop_imm(&image, x, y, width, height).to_image();
+        }
+        Ok(image)
     }
 
     pub fn dispatch_window_event(
@@ -290,6 +311,19 @@ pub fn dispatch_window_event(
         Ok(())
     }
 
+    /// Asks the window to close, like the user clicking the close button would. Returns
+    /// false if the application's close-requested callback kept the window shown.
+    #[cfg(feature = "mcp")]
+    pub fn close_window(&self, window_index: ArenaIndex) -> Result<bool, String> {
+        let adapter = self.window_adapter(window_index)?;
+        let window = adapter.window();
+        if !WindowInner::from_pub(window).request_close() {
+            return Ok(false);
+        }
+        window.hide().map_err(|e| format!("failed to hide window: {e}"))?;
+        Ok(true)
+    }
+
     pub fn record_window_event(
         &self,
         adapter: &Rc<dyn WindowAdapter>,
@@ -433,15 +467,6 @@ pub fn window_properties(
             scale_factor: window.scale_factor(),
         })
     }
-
-    pub fn take_snapshot_response(
-        &self,
-        window_index: ArenaIndex,
-        image_mime_type: &str,
-    ) -> Result<proto::TakeSnapshotResponse, String> {
-        let window_contents_as_encoded_image = self.take_snapshot(window_index, image_mime_type)?;
-        Ok(proto::TakeSnapshotResponse { window_contents_as_encoded_image })
-    }
 }
 
 /// Returned when a [`i_slint_core::platform::WindowEvent`] or
@@ -548,6 +573,8 @@ fn convert_event_dispatch_result(
 // Shared proto ↔ core conversion functions
 // ============================================================================
 
+/// Returns the properties of `element`, except for `z_order`: computing it walks the whole
+/// window, so it's only filled in by [`element_properties_with_z_order`], for single elements.
 pub(crate) fn element_properties(element: &ElementHandle) -> proto::ElementPropertiesResponse {
     let type_names_and_ids = core::iter::once(proto::ElementTypeNameAndId {
         type_name: element.type_name().unwrap_or_default().into(),
@@ -595,9 +622,37 @@ pub(crate) fn element_properties(element: &ElementHandle) -> proto::ElementPrope
             Some(LayoutKind::FlexboxLayout) => proto::LayoutKind::FlexboxLayout.into(),
             None => proto::LayoutKind::NotALayout.into(),
         },
+        supported_actions: supported_actions(element),
+        sibling_index: element.sibling_index().and_then(|index| index.try_into().ok()),
+        z_order: None,
+    }
+}
+
+/// Returns the properties of `element` like [`element_properties`], including `z_order`.
+pub(crate) fn element_properties_with_z_order(
+    element: &ElementHandle,
+) -> proto::ElementPropertiesResponse {
+    proto::ElementPropertiesResponse {
+        z_order: element.z_order().and_then(|order| order.try_into().ok()),
+        ..element_properties(element)
     }
 }
 
+fn supported_actions(element: &ElementHandle) -> Vec<i32> {
+    use i_slint_core::accessibility::SupportedAccessibilityAction as Supported;
+    let supported = element.supported_accessibility_actions();
+    [
+        (Supported::Default, proto::ElementAccessibilityAction::Default),
+        (Supported::Increment, proto::ElementAccessibilityAction::Increment),
+        (Supported::Decrement, proto::ElementAccessibilityAction::Decrement),
+        (Supported::Expand, proto::ElementAccessibilityAction::Expand),
+    ]
+    .into_iter()
+    .filter(|(flag, _)| supported.contains(*flag))
+    .map(|(_, action)| action.into())
+    .collect()
+}
+
 pub(crate) fn query_element_descendants(
     element: ElementHandle,
     query_stack: Vec<proto::ElementQueryInstruction>,
@@ -621,18 +676,238 @@ pub(crate) fn query_element_descendants(
                 query = query.match_inherits(type_name_or_base)
             }
             Instruction::MatchElementAccessibleRole(role_i32) => {
-                let role = proto::AccessibleRole::try_from(role_i32)
-                    .map_err(|_| format!("invalid AccessibleRole value: {role_i32}"))?;
-                query = query.match_accessible_role(
-                    convert_from_proto_accessible_role(role)
-                        .ok_or_else(|| "Unknown accessibility role".to_string())?,
-                )
+                query = query.match_accessible_role(accessible_role_from_i32(role_i32)?)
+            }
+            Instruction::MatchAnyOf(any_of) => {
+                if any_of.alternatives.is_empty() {
+                    return Err("match_any_of requires at least one alternative".into());
+                }
+                let alternatives = any_of
+                    .alternatives
+                    .into_iter()
+                    .map(match_alternative)
+                    .collect::<Result<Vec<_>, _>>()?;
+                query = query.match_predicate(move |element| {
+                    alternatives.iter().any(|matches| matches(element))
+                })
             }
         }
     }
     Ok(if find_all { query.find_all() } else { query.find_first().into_iter().collect() })
 }
 
+fn accessible_role_from_i32(role_i32: i32) -> Result<i_slint_core::items::AccessibleRole, String> {
+    let role = proto::AccessibleRole::try_from(role_i32)
+        .map_err(|_| format!("invalid AccessibleRole value: {role_i32}"))?;
+    convert_from_proto_accessible_role(role).ok_or_else(|| "Unknown accessibility role".to_string())
+}
+
+type ElementMatcher = Box<dyn Fn(&ElementHandle) -> bool>;
+
+fn match_alternative(
+    alternative: proto::ElementMatchAlternative,
+) -> Result<ElementMatcher, String> {
+    use proto::element_match_alternative::Alternative;
+    Ok(
+        match alternative.alternative.ok_or_else(|| "empty match_any_of alternative".to_string())? {
+            Alternative::MatchElementTypeName(type_name) => Box::new(move |element| {
+                element.type_name().is_some_and(|candidate| candidate == type_name)
+            }),
+            Alternative::MatchElementTypeNameOrBase(type_name) => Box::new(move |element| {
+                element.type_name().is_some_and(|candidate| candidate == type_name)
+                    || element.bases().is_some_and(|mut bases| bases.any(|base| base == type_name))
+            }),
+            Alternative::MatchElementAccessibleRole(role_i32) => {
+                let role = accessible_role_from_i32(role_i32)?;
+                Box::new(move |element| element.accessible_role() == Some(role))
+            }
+        },
+    )
+}
+
+/// Converts a logical element rectangle into an `(x, y, width, height)` pixel rectangle within
+/// an image of `image_width` × `image_height` physical pixels, clamping it to the image bounds.
+/// Returns `None` if nothing of the rectangle is left after clamping.
+fn physical_crop_rect(
+    origin: i_slint_core::api::LogicalPosition,
+    size: i_slint_core::api::LogicalSize,
+    scale_factor: f32,
+    image_width: u32,
+    image_height: u32,
+) -> Option<(u32, u32, u32, u32)> {
+    let clamp = |value: f32, max: u32| value.clamp(0., max as f32) as u32;
+    let left = clamp((origin.x * scale_factor).floor(), image_width);
+    let top = clamp((origin.y * scale_factor).floor(), image_height);
+    let right = clamp(((origin.x + size.width) * scale_factor).ceil(), image_width);
+    let bottom = clamp(((origin.y + size.height) * scale_factor).ceil(), image_height);
+    (right > left && bottom > top).then(|| (left, top, right - left, bottom - top))
+}
+
+/// A window state that can be toggled through `dispatch::set_window_state`.
+#[cfg(feature = "mcp")]
+#[derive(Clone, Copy)]
+pub(crate) enum WindowState {
+    Fullscreen,
+    Maximized,
+    Minimized,
+}
+
+#[cfg(feature = "mcp")]
+impl WindowState {
+    fn name(self) -> &'static str {
+        match self {
+            WindowState::Fullscreen => "fullscreen",
+            WindowState::Maximized => "maximized",
+            WindowState::Minimized => "minimized",
+        }
+    }
+}
+
+/// Outcome of comparing two images of the same size pixel by pixel.
+#[cfg(feature = "mcp")]
+pub(crate) struct ImageComparison {
+    pub differing_pixels: u64,
+    /// The baseline in dimmed grayscale, with the differing pixels in red.
+    pub diff_image: image::RgbaImage,
+}
+
+/// Compares two images of the same size. A pixel differs if any channel differs by more
+/// than `tolerance`. Returns `None` if the sizes do not match.
+#[cfg(feature = "mcp")]
+pub(crate) fn compare_images(
+    baseline: &image::RgbaImage,
+    candidate: &image::RgbaImage,
+    tolerance: u8,
+) -> Option<ImageComparison> {
+    if baseline.dimensions() != candidate.dimensions() {
+        return None;
+    }
+    let mut differing_pixels = 0;
+    let diff_image = image::RgbaImage::from_fn(baseline.width(), baseline.height(), |x, y| {
+        let a = baseline.get_pixel(x, y);
+        let b = candidate.get_pixel(x, y);
+        if a.0.iter().zip(b.0.iter()).any(|(a, b)| a.abs_diff(*b) > tolerance) {
+            differing_pixels += 1;
+            image::Rgba([255, 0, 0, 255])
+        } else {
+            // Luma, compressed into the light end so that the red stands out.
+            let [r, g, b, _] = a.0.map(u32::from);
+            let gray = ((r * 3 + g * 6 + b) / 30 + 170) as u8;
+            image::Rgba([gray, gray, gray, 255])
+        }
+    });
+    Some(ImageComparison { differing_pixels, diff_image })
+}
+
+/// Maps the `quality` field of a snapshot request to a JPEG quality, clamping it to 1..=100.
+/// 0 selects the encoder default.
+pub(crate) fn jpeg_quality(quality: u32) -> Option<u8> {
+    (quality != 0).then(|| quality.min(100) as u8)
+}
+
+/// Encodes `image` in `format`. `quality` only applies to JPEG; the other formats are lossless.
+pub(crate) fn encode_image(
+    image: image::RgbaImage,
+    format: image::ImageFormat,
+    quality: Option<u8>,
+) -> Result<Vec<u8>, image::ImageError> {
+    let mut encoded: Vec<u8> = Vec::new();
+    let mut cursor = std::io::Cursor::new(&mut encoded);
+    match (format, quality) {
+        (image::ImageFormat::Jpeg, Some(quality)) => {
+            // JPEG has no alpha channel
+            let rgb = image::DynamicImage::ImageRgba8(image).to_rgb8();
+            image::codecs::jpeg::JpegEncoder::new_with_quality(&mut cursor, quality)
+                .encode_image(&rgb)?;
+        }
+        (image::ImageFormat::Jpeg, None) => {
+            image::DynamicImage::ImageRgba8(image).to_rgb8().write_to(&mut cursor, format)?;
+        }
+        _ => image.write_to(&mut cursor, format)?,
+    }
+    Ok(encoded)
+}
+
+/// Whether the element has zero size or zero opacity.
+#[cfg(feature = "mcp")]
+pub(crate) fn is_invisible(element: &ElementHandle) -> bool {
+    let size = element.size();
+    size.width <= 0. || size.height <= 0. || element.computed_opacity() <= 0.
+}
+
+/// Collects the accessible label and value of every visible element with an accessible role
+/// in `root`'s subtree, sorted top to bottom and then left to right.
+#[cfg(feature = "mcp")]
+pub(crate) fn text_in_reading_order(root: &ElementHandle) -> Vec<(ElementHandle, String)> {
+    let mut entries = Vec::new();
+    let mut collect = |element: ElementHandle| {
+        if element
+            .accessible_role()
+            .is_none_or(|role| role == i_slint_core::items::AccessibleRole::None)
+            || is_invisible(&element)
+        {
+            return;
+        }
+        let text = [element.accessible_label(), element.accessible_value()]
+            .into_iter()
+            .flatten()
+            .filter(|text| !text.is_empty())
+            .map(|text| text.to_string())
+            .collect::<Vec<_>>()
+            .join(" ");
+        if !text.is_empty() {
+            entries.push((element, text));
+        }
+    };
+    collect(root.clone());
+    root.visit_descendants(|element| {
+        collect(element);
+        std::ops::ControlFlow::<()>::Continue(())
+    });
+    entries.sort_by(|(a, _), (b, _)| reading_order(a.absolute_position(), b.absolute_position()));
+    entries
+}
+
+/// Orders positions top to bottom, then left to right.
+#[cfg(feature = "mcp")]
+fn reading_order(
+    a: i_slint_core::api::LogicalPosition,
+    b: i_slint_core::api::LogicalPosition,
+) -> std::cmp::Ordering {
+    a.y.total_cmp(&b.y).then(a.x.total_cmp(&b.x))
+}
+
+/// Returns the topmost element in `root`'s subtree (including `root` itself) whose bounding
+/// rectangle contains `position`. Elements later in tree order win, so the deepest element
+/// wins over its ancestors and a later sibling wins over an earlier, overlapping one — which
+/// matches the order in which they are painted. Elements that aren't painted at `position`
+/// are skipped: those with zero opacity, and those clipped away by an ancestor, like the
+/// children of a `Flickable` that are scrolled out of its viewport.
+#[cfg(feature = "mcp")]
+pub(crate) fn element_at_position(
+    root: &ElementHandle,
+    position: i_slint_core::api::LogicalPosition,
+) -> Option<ElementHandle> {
+    let contains = |element: &ElementHandle| {
+        let origin = element.absolute_position();
+        let size = element.size();
+        position.x >= origin.x
+            && position.y >= origin.y
+            && position.x < origin.x + size.width
+            && position.y < origin.y + size.height
+            && !is_invisible(element)
+            && element.is_unclipped_at(position)
+    };
+    let mut hit = contains(root).then(|| root.clone());
+    root.visit_descendants(|element| {
+        if contains(&element) {
+            hit = Some(element);
+        }
+        std::ops::ControlFlow::<()>::Continue(())
+    });
+    hit
+}
+
 pub(crate) fn invoke_element_accessibility_action(
     element: &ElementHandle,
     action: proto::ElementAccessibilityAction,
@@ -787,18 +1062,100 @@ pub(crate) fn window_properties(
         state.window_properties(window)
     }
 
+    /// Asks the window to resize and returns the resulting window properties, which may
+    /// differ from the request if the windowing system adjusted it.
+    #[cfg(feature = "mcp")]
+    pub(crate) fn set_window_size(
+        state: &IntrospectionState,
+        window: ArenaIndex,
+        size: proto::PhysicalSize,
+    ) -> Result<proto::WindowPropertiesResponse, String> {
+        let adapter = state.window_adapter(window)?;
+        adapter.window().set_size(i_slint_core::api::PhysicalSize::new(size.width, size.height));
+        state.window_properties(window)
+    }
+
+    /// Asks the window to move and returns the resulting window properties, which may
+    /// differ from the request if the windowing system does not support positioning.
+    #[cfg(feature = "mcp")]
+    pub(crate) fn set_window_position(
+        state: &IntrospectionState,
+        window: ArenaIndex,
+        position: proto::PhysicalPosition,
+    ) -> Result<proto::WindowPropertiesResponse, String> {
+        let adapter = state.window_adapter(window)?;
+        adapter
+            .window()
+            .set_position(i_slint_core::api::PhysicalPosition::new(position.x, position.y));
+        state.window_properties(window)
+    }
+
+    /// Switches one of the window states reported by `window_properties` on or off and
+    /// returns the updated properties. Fails if the window did not take on the state.
+    #[cfg(feature = "mcp")]
+    pub(crate) fn set_window_state(
+        state: &IntrospectionState,
+        window: ArenaIndex,
+        which: super::WindowState,
+        enabled: bool,
+    ) -> Result<proto::WindowPropertiesResponse, String> {
+        use super::WindowState;
+        let adapter = state.window_adapter(window)?;
+        let window_api = adapter.window();
+        match which {
+            WindowState::Fullscreen => window_api.set_fullscreen(enabled),
+            WindowState::Maximized => window_api.set_maximized(enabled),
+            WindowState::Minimized => window_api.set_minimized(enabled),
+        }
+        let properties = state.window_properties(window)?;
+        let current = match which {
+            WindowState::Fullscreen => properties.is_fullscreen,
+            WindowState::Maximized => properties.is_maximized,
+            WindowState::Minimized => properties.is_minimized,
+        };
+        if current != enabled {
+            return Err(format!("the window does not support changing its {} state", which.name()));
+        }
+        Ok(properties)
+    }
+
+    #[cfg(feature = "mcp")]
+    pub(crate) fn close_window(
+        state: &IntrospectionState,
+        window: ArenaIndex,
+    ) -> Result<bool, String> {
+        state.close_window(window)
+    }
+
+    /// Registers handles for the at most `limit` elements starting at `offset`, and returns
+    /// them with the total number of elements and the offset of the next page, if any.
+    fn paginate(
+        state: &IntrospectionState,
+        elements: Vec<crate::ElementHandle>,
+        offset: u32,
+        limit: Option<u32>,
+    ) -> (Vec<proto::Handle>, u32, Option<u32>) {
+        let total = elements.len();
+        let start = (offset as usize).min(total);
+        let end = limit.map_or(total, |limit| start.saturating_add(limit as usize).min(total));
+        let handles = elements[start..end]
+            .iter()
+            .map(|e| index_to_handle(state.element_to_handle(e.clone())))
+            .collect();
+        let next_offset = (end < total).then(|| end.try_into().unwrap_or(u32::MAX));
+        (handles, total.try_into().unwrap_or(u32::MAX), next_offset)
+    }
+
     pub(crate) fn find_elements_by_id(
         state: &IntrospectionState,
         window: ArenaIndex,
         elements_id: &str,
+        offset: u32,
+        limit: Option<u32>,
     ) -> Result<proto::ElementsResponse, String> {
         let elements = state.find_elements_by_id(window, elements_id)?;
-        Ok(proto::ElementsResponse {
-            element_handles: elements
-                .into_iter()
-                .map(|e| index_to_handle(state.element_to_handle(e)))
-                .collect(),
-        })
+        let (element_handles, total, next_offset) = paginate(state, elements, offset, limit);
+        Ok(proto::ElementsResponse { element_handles, total, next_offset })
     }
 
     pub(crate) fn element_properties(
@@ -806,7 +1163,28 @@ pub(crate) fn element_properties(
         element: ArenaIndex,
     ) -> Result<proto::ElementPropertiesResponse, String> {
         let element = state.element("element_properties", element)?;
-        Ok(super::element_properties(&element))
+        Ok(super::element_properties_with_z_order(&element))
+    }
+
+    /// Returns all elements in the window with the given accessible role.
+    #[cfg(feature = "mcp")]
+    pub(crate) fn find_elements_by_role(
+        state: &IntrospectionState,
+        window: ArenaIndex,
+        role: proto::AccessibleRole,
+    ) -> Result<proto::ElementsResponse, String> {
+        use proto::element_query_instruction::Instruction;
+        let root = state.element("find_elements_by_role", state.root_element_handle(window)?)?;
+        let query_stack = [
+            Instruction::MatchDescendants(true),
+            Instruction::MatchElementAccessibleRole(role.into()),
+        ]
+        .into_iter()
+        .map(|instruction| proto::ElementQueryInstruction { instruction: Some(instruction) })
+        .collect();
+        let elements = super::query_element_descendants(root, query_stack, true)?;
+        let (element_handles, total, next_offset) = paginate(state, elements, 0, None);
+        Ok(proto::ElementsResponse { element_handles, total, next_offset })
     }
 
     pub(crate) fn query_element_descendants(
@@ -814,23 +1192,117 @@ pub(crate) fn query_element_descendants(
         element: ArenaIndex,
         query_stack: Vec<proto::ElementQueryInstruction>,
         find_all: bool,
+        offset: u32,
+        limit: Option<u32>,
     ) -> Result<proto::ElementQueryResponse, String> {
         let element = state.element("query_element_descendants", element)?;
         let results = super::query_element_descendants(element, query_stack, find_all)?;
-        Ok(proto::ElementQueryResponse {
-            element_handles: results
-                .into_iter()
-                .map(|e| index_to_handle(state.element_to_handle(e)))
-                .collect(),
-        })
+        let (element_handles, total, next_offset) = paginate(state, results, offset, limit);
+        Ok(proto::ElementQueryResponse { element_handles, total, next_offset })
+    }
+
+    /// Returns the handle and properties of the topmost element at `position`, if any.
+    #[cfg(feature = "mcp")]
+    pub(crate) fn element_at_position(
+        state: &IntrospectionState,
+        window: ArenaIndex,
+        position: proto::LogicalPosition,
+    ) -> Result<Option<(proto::Handle, proto::ElementPropertiesResponse)>, String> {
+        let root = state.element("element_at_position", state.root_element_handle(window)?)?;
+        let position = i_slint_core::api::LogicalPosition::new(position.x, position.y);
+        Ok(super::element_at_position(&root, position).map(|element| {
+            let properties = super::element_properties_with_z_order(&element);
+            (index_to_handle(state.element_to_handle(element)), properties)
+        }))
+    }
+
+    /// Returns the visible text of the window with the handle and position of the element
+    /// it belongs to, in reading order.
+    #[cfg(feature = "mcp")]
+    pub(crate) fn window_text(
+        state: &IntrospectionState,
+        window: ArenaIndex,
+    ) -> Result<Vec<(String, proto::Handle, proto::LogicalPosition)>, String> {
+        let root = state.element("window_text", state.root_element_handle(window)?)?;
+        Ok(super::text_in_reading_order(&root)
+            .into_iter()
+            .map(|(element, text)| {
+                let position = element.absolute_position();
+                let handle = index_to_handle(state.element_to_handle(element));
+                (text, handle, proto::LogicalPosition { x: position.x, y: position.y })
+            })
+            .collect())
+    }
+
+    #[cfg(feature = "mcp")]
+    fn clipboard_kind(selection: bool) -> i_slint_core::platform::Clipboard {
+        if selection {
+            i_slint_core::platform::Clipboard::SelectionClipboard
+        } else {
+            i_slint_core::platform::Clipboard::DefaultClipboard
+        }
+    }
+
+    /// Reads the clipboard through the platform of the window's application.
+    #[cfg(feature = "mcp")]
+    pub(crate) fn clipboard_text(
+        state: &IntrospectionState,
+        window: ArenaIndex,
+        selection: bool,
+    ) -> Result<Option<String>, String> {
+        let adapter = state.window_adapter(window)?;
+        Ok(super::WindowInner::from_pub(adapter.window())
+            .context()
+            .platform()
+            .clipboard_text(clipboard_kind(selection)))
+    }
+
+    /// Writes the clipboard through the platform of the window's application. Platforms
+    /// without (selection) clipboard support ignore this.
+    #[cfg(feature = "mcp")]
+    pub(crate) fn set_clipboard_text(
+        state: &IntrospectionState,
+        window: ArenaIndex,
+        text: &str,
+        selection: bool,
+    ) -> Result<(), String> {
+        let adapter = state.window_adapter(window)?;
+        super::WindowInner::from_pub(adapter.window())
+            .context()
+            .platform()
+            .set_clipboard_text(text, clipboard_kind(selection));
+        Ok(())
+    }
+
+    /// Returns the handle and properties of the element with keyboard focus, if any.
+    #[cfg(feature = "mcp")]
+    pub(crate) fn focused_element(
+        state: &IntrospectionState,
+        window: ArenaIndex,
+    ) -> Result<Option<(proto::Handle, proto::ElementPropertiesResponse)>, String> {
+        let adapter = state.window_adapter(window)?;
+        Ok(crate::ElementHandle::focused(adapter.window()).map(|element| {
+            let properties = super::element_properties_with_z_order(&element);
+            (index_to_handle(state.element_to_handle(element)), properties)
+        }))
+    }
+
+    #[cfg(feature = "mcp")]
+    pub(crate) fn capture_window(
+        state: &IntrospectionState,
+        window: ArenaIndex,
+    ) -> Result<image::RgbaImage, String> {
+        state.capture_window(window, None)
     }
 
     pub(crate) fn take_snapshot(
         state: &IntrospectionState,
         window: ArenaIndex,
         image_mime_type: &str,
+        crop_to: Option<ArenaIndex>,
+        quality: Option<u8>,
     ) -> Result<proto::TakeSnapshotResponse, String> {
-        state.take_snapshot_response(window, image_mime_type)
+        state.take_snapshot(window, image_mime_type, crop_to, quality)
     }
 
     #[cfg(feature = "system-testing")]
@@ -898,6 +1370,45 @@ pub(crate) async fn click(
         Ok(())
     }
 
+    /// Returns the center of the element in logical window coordinates.
+    #[cfg(feature = "mcp")]
+    pub(crate) fn element_center(
+        state: &IntrospectionState,
+        element: ArenaIndex,
+    ) -> Result<proto::LogicalPosition, String> {
+        let element = state.element("element_center", element)?;
+        let origin = element.absolute_position();
+        let size = element.size();
+        Ok(proto::LogicalPosition { x: origin.x + size.width / 2., y: origin.y + size.height / 2. })
+    }
+
+    /// Moves the pointer over the element's center and returns that position.
+    #[cfg(feature = "mcp")]
+    pub(crate) fn hover(
+        state: &IntrospectionState,
+        element: ArenaIndex,
+    ) -> Result<proto::LogicalPosition, String> {
+        let position = element_center(state, element)?;
+        state.element("hover", element)?.hover();
+        Ok(position)
+    }
+
+    /// Drags the element from its center to `target` with `steps` interpolated moves.
+    #[cfg(feature = "mcp")]
+    pub(crate) async fn drag_to(
+        state: &IntrospectionState,
+        element: ArenaIndex,
+        target: proto::LogicalPosition,
+        button: proto::PointerEventButton,
+        steps: usize,
+    ) -> Result<(), String> {
+        let element = state.element("drag_to", element)?;
+        let button = convert_pointer_event_button(button);
+        let target = i_slint_core::api::LogicalPosition::new(target.x, target.y);
+        element.drag_in_steps(target, button, steps).await;
+        Ok(())
+    }
+
     pub(crate) async fn drag(
         state: &IntrospectionState,
         element: ArenaIndex,
@@ -926,7 +1437,8 @@ fn test_dispatch_element_properties_stale_handle() {
 #[test]
 fn test_dispatch_find_elements_by_id_stale_window() {
     let state = IntrospectionState::new();
-    let err = dispatch::find_elements_by_id(&state, ArenaIndex::default(), "foo").unwrap_err();
+    let err =
+        dispatch::find_elements_by_id(&state, ArenaIndex::default(), "foo", 0, None).unwrap_err();
     assert!(err.contains("Invalid window handle"), "got: {err}");
 }
 
@@ -946,6 +1458,207 @@ fn test_dispatch_click_double_click_stale_handle() {
     });
 }
 
+#[test]
+fn test_physical_crop_rect() {
+    use i_slint_core::api::{LogicalPosition, LogicalSize};
+    let rect = |x, y, w, h, scale| {
+        physical_crop_rect(LogicalPosition::new(x, y), LogicalSize::new(w, h), scale, 200, 100)
+    };
+    assert_eq!(rect(10., 20., 30., 40., 1.), Some((10, 20, 30, 40)));
+    assert_eq!(rect(10., 20., 30., 20., 2.), Some((20, 40, 60, 40)));
+    // Fractional edges round outwards so the element is fully contained.
+    assert_eq!(rect(10.5, 20., 1., 1., 1.), Some((10, 20, 2, 1)));
+    // Extending beyond the image is clamped.
+    assert_eq!(rect(-10., 90., 50., 50., 1.), Some((0, 90, 40, 10)));
+    assert_eq!(rect(250., 0., 10., 10., 1.), None);
+    assert_eq!(rect(10., 10., 0., 10., 1.), None);
+}
+
+#[cfg(feature = "mcp")]
+#[test]
+fn test_compare_images() {
+    let baseline = image::RgbaImage::from_pixel(4, 2, image::Rgba([10, 20, 30, 255]));
+    let mut candidate = baseline.clone();
+    candidate.put_pixel(0, 0, image::Rgba([12, 20, 30, 255]));
+    candidate.put_pixel(3, 1, image::Rgba([10, 20, 200, 255]));
+
+    let strict = compare_images(&baseline, &candidate, 0).unwrap();
+    assert_eq!(strict.differing_pixels, 2);
+    assert_eq!(strict.diff_image.dimensions(), (4, 2));
+    assert_eq!(*strict.diff_image.get_pixel(3, 1), image::Rgba([255, 0, 0, 255]));
+    assert_ne!(*strict.diff_image.get_pixel(1, 0), image::Rgba([255, 0, 0, 255]));
+    // Small differences are within the tolerance.
+    assert_eq!(compare_images(&baseline, &candidate, 5).unwrap().differing_pixels, 1);
+    assert_eq!(compare_images(&baseline, &baseline, 0).unwrap().differing_pixels, 0);
+
+    let smaller = image::RgbaImage::new(2, 2);
+    assert!(compare_images(&baseline, &smaller, 0).is_none());
+}
+
+#[test]
+fn test_encode_image_jpeg_quality() {
+    assert_eq!(jpeg_quality(0), None);
+    assert_eq!(jpeg_quality(50), Some(50));
+    assert_eq!(jpeg_quality(1000), Some(100));
+
+    // A noisy image, so that the quality setting makes a visible difference in size.
+    let image = image::RgbaImage::from_fn(64, 64, |x, y| {
+        image::Rgba([(x * 37 % 256) as u8, (y * 91 % 256) as u8, ((x ^ y) * 13 % 256) as u8, 255])
+    });
+    let jpeg = |quality| encode_image(image.clone(), image::ImageFormat::Jpeg, quality).unwrap();
+    assert!(jpeg(Some(10)).len() < jpeg(Some(95)).len());
+    // Quality is ignored for lossless formats.
+    let png = |quality| encode_image(image.clone(), image::ImageFormat::Png, quality).unwrap();
+    assert_eq!(png(Some(10)), png(None));
+}
+
+#[cfg(feature = "mcp")]
+#[test]
+fn test_element_at_position_prefers_topmost() {
+    crate::init_no_event_loop();
+    slint::slint! {
+        export component App inherits Window {
+            width: 200px;
+            height: 200px;
+            outer := Rectangle {
+                x: 0px; y: 0px; width: 100px; height: 100px;
+                inner := Rectangle { x: 10px; y: 10px; width: 20px; height: 20px; }
+            }
+            overlay := Rectangle { x: 80px; y: 80px; width: 40px; height: 40px; }
+        }
+    }
+    let app = App::new().unwrap();
+    let root = crate::ElementRoot::root_element(&app);
+    let id_at = |x, y| {
+        element_at_position(&root, i_slint_core::api::LogicalPosition::new(x, y))
+            .and_then(|element| element.id())
+            .map(|id| id.to_string())
+    };
+
+    assert_eq!(id_at(15., 15.).as_deref(), Some("App::inner"));
+    assert_eq!(id_at(50., 50.).as_deref(), Some("App::outer"));
+    // `overlay` comes after `outer` in tree order, so it is painted on top where they overlap.
+    assert_eq!(id_at(90., 90.).as_deref(), Some("App::overlay"));
+    assert!(
+        element_at_position(&root, i_slint_core::api::LogicalPosition::new(150., 150.)).is_some()
+    );
+    assert!(
+        element_at_position(&root, i_slint_core::api::LogicalPosition::new(250., 50.)).is_none()
+    );
+}
+
+#[cfg(feature = "mcp")]
+#[test]
+fn test_element_at_position_skips_unpainted_elements() {
+    crate::init_no_event_loop();
+    slint::slint! {
+        export component App inherits Window {
+            width: 200px;
+            height: 200px;
+            button := Rectangle {
+                x: 0px; y: 0px; width: 100px; height: 40px;
+                accessible-role: button;
+            }
+            overlay := Rectangle { x: 0px; y: 0px; width: 200px; height: 200px; opacity: 0; }
+            viewport := Rectangle {
+                x: 0px; y: 100px; width: 100px; height: 50px;
+                clip: true;
+                partly-visible := Rectangle { x: 0px; y: 30px; width: 100px; height: 40px; }
+            }
+        }
+    }
+    let app = App::new().unwrap();
+    let root = crate::ElementRoot::root_element(&app);
+    let id_at = |x, y| {
+        element_at_position(&root, i_slint_core::api::LogicalPosition::new(x, y))
+            .and_then(|element| element.id())
+            .map(|id| id.to_string())
+    };
+
+    // The transparent overlay is later in tree order, but isn't painted.
+    assert_eq!(id_at(50., 20.).as_deref(), Some("App::button"));
+    // `partly-visible` is hit within its clipping parent, but not where it's clipped away.
+    assert_eq!(id_at(50., 140.).as_deref(), Some("App::partly-visible"));
+    let clipped = id_at(50., 160.);
+    assert!(clipped.is_some());
+    assert_ne!(clipped.as_deref(), Some("App::partly-visible"));
+}
+
+#[test]
+fn test_query_match_any_of() {
+    use proto::element_match_alternative::Alternative;
+    use proto::element_query_instruction::Instruction;
+    crate::init_no_event_loop();
+    slint::slint! {
+        export component App inherits Window {
+            ok := Rectangle { accessible-role: button; }
+            agree := Rectangle { accessible-role: checkbox; }
+            label := Rectangle { accessible-role: text; }
+            caption := Text {}
+        }
+    }
+    let app = App::new().unwrap();
+    let root = crate::ElementRoot::root_element(&app);
+    let query = |alternatives: Vec<Alternative>| {
+        let alternatives = alternatives
+            .into_iter()
+            .map(|alternative| proto::ElementMatchAlternative { alternative: Some(alternative) })
+            .collect();
+        let stack = vec![proto::ElementQueryInstruction {
+            instruction: Some(Instruction::MatchAnyOf(proto::ElementMatchAnyOf { alternatives })),
+        }];
+        query_element_descendants(root.clone(), stack, true)
+    };
+    let role = |role: proto::AccessibleRole| Alternative::MatchElementAccessibleRole(role.into());
+
+    let found =
+        query(vec![role(proto::AccessibleRole::Button), role(proto::AccessibleRole::Checkbox)])
+            .unwrap();
+    assert_eq!(
+        found.iter().map(|e| e.accessible_role()).collect::<Vec<_>>(),
+        vec![
+            Some(i_slint_core::items::AccessibleRole::Button),
+            Some(i_slint_core::items::AccessibleRole::Checkbox)
+        ]
+    );
+    let found = query(vec![
+        role(proto::AccessibleRole::Button),
+        Alternative::MatchElementTypeName("Text".into()),
+    ])
+    .unwrap();
+    assert_eq!(found.len(), 2);
+    assert!(query(vec![]).is_err_and(|e| e.contains("at least one alternative")));
+}
+
+#[cfg(feature = "mcp")]
+#[test]
+fn test_text_in_reading_order() {
+    crate::init_no_event_loop();
+    slint::slint! {
+        export component App inherits Window {
+            width: 200px;
+            height: 200px;
+            Text { x: 100px; y: 50px; text: "second"; }
+            Text { x: 10px; y: 100px; text: "fourth"; }
+            Text { x: 10px; y: 50px; text: "first"; }
+            Text { x: 150px; y: 50px; text: "third"; }
+            Text { x: 10px; y: 150px; text: "hidden"; opacity: 0; }
+            input := TextInput { x: 10px; y: 180px; text: "typed"; }
+        }
+    }
+    let app = App::new().unwrap();
+    let root = crate::ElementRoot::root_element(&app);
+    let texts: Vec<String> =
+        text_in_reading_order(&root).into_iter().map(|(_, text)| text).collect();
+    assert_eq!(texts, ["first", "second", "third", "fourth", "typed"]);
+
+    use i_slint_core::api::LogicalPosition;
+    let at = |x, y| LogicalPosition::new(x, y);
+    assert_eq!(reading_order(at(50., 10.), at(10., 20.)), std::cmp::Ordering::Less);
+    assert_eq!(reading_order(at(50., 10.), at(10., 10.)), std::cmp::Ordering::Greater);
+    assert_eq!(reading_order(at(10., 10.), at(10., 10.)), std::cmp::Ordering::Equal);
+}
+
 #[test]
 fn test_handle_to_index_rejects_noncanonical_generation() {
     assert!(handle_to_index(proto::Handle { index: 42, generation: 6 }).is_err());
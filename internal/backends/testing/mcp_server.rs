@@ -5,7 +5,9 @@
 //!
 //! When enabled via the `SLINT_MCP_PORT` environment variable, the application starts
 //! an HTTP server implementing MCP's Streamable HTTP transport, allowing MCP clients
-//! (e.g. Claude) to inspect and interact with the running UI.
+//! (e.g. Claude) to inspect and interact with the running UI. Alternatively,
+//! `SLINT_MCP_TRANSPORT=stdio` serves MCP over stdin/stdout, for clients that launch
+//! the application as a subprocess.
 //!
 //! # Usage
 //!
@@ -13,6 +15,17 @@
 //! ```sh
 //! SLINT_MCP_PORT=8080 ./your-slint-app
 //! ```
+//!
+//! `SLINT_MCP_REQUEST_TIMEOUT_MS` (default: 30000) limits how long the server waits for a
+//! client to send a complete HTTP request, once its first byte arrived, before dropping the
+//! connection.
+//! `SLINT_MCP_IDLE_TIMEOUT_MS` (default: 300000) limits how long a keep-alive connection may
+//! wait for the next request before it's closed.
+//! `SLINT_MCP_MAX_BODY_MB` (default: 4) limits the size of a request body.
+//! `SLINT_MCP_SCREENSHOT_DIR` (default: `slint-mcp-screenshots` in the temporary directory)
+//! is where `save_screenshot` writes its files and `compare_screenshots` reads baselines from.
+//! `SLINT_MCP_LOG_FILE` appends every request and its response to the given file, one JSON
+//! object per line, for debugging clients.
 
 use base64::Engine;
 use futures_lite::{AsyncReadExt, AsyncWriteExt};
@@ -48,41 +61,168 @@ struct ToolDef {
         request_type: "RequestWindowListMessage",
         optional_fields: &[],
     },
+    ToolDef {
+        name: "ping",
+        description: "Cheap health check. Returns {connected: true, windowCount, latencyMs}, where latencyMs is the time the application took to answer. Use it to confirm the application is still alive and responsive before a longer sequence of calls; if it fails or times out, the application has exited or its event loop is blocked.",
+        request_type: "RequestWindowListMessage",
+        optional_fields: &[],
+    },
+    ToolDef {
+        name: "get_server_info",
+        description: "Describe this MCP server. Returns {name, version, transport, port, toolCount}: version is the Slint version the server was built with, transport is \"http\" or \"stdio\" (port is only set for http), and toolCount is the number of tools available.",
+        request_type: "RequestWindowListMessage",
+        optional_fields: &[],
+    },
     ToolDef {
         name: "get_window_properties",
         description: "Get a window's physical size (pixels), position, scale factor, fullscreen/maximized/minimized state, and rootElementHandle — the entry point for element tree traversal.",
         request_type: "RequestWindowProperties",
         optional_fields: &[],
     },
+    ToolDef {
+        name: "set_window_size",
+        description: "Resize a window to the given size in physical pixels, e.g. to test responsive layouts. Returns the resulting window properties (same as get_window_properties) plus applied, which is false if the windowing system adjusted or rejected the size — the properties then report the actual size.",
+        request_type: "RequestSetWindowSize",
+        optional_fields: &[],
+    },
+    ToolDef {
+        name: "set_window_position",
+        description: "Move a window to the given position in physical screen coordinates. Returns the resulting window properties (same as get_window_properties) plus applied, which is false if the windowing system ignored or adjusted the position (some platforms, such as Wayland, do not let applications position windows).",
+        request_type: "RequestSetWindowPosition",
+        optional_fields: &[],
+    },
+    ToolDef {
+        name: "set_fullscreen",
+        description: "Enter (fullscreen=true) or leave (fullscreen=false) fullscreen mode. Returns the updated window properties, or an error if the window does not support the change.",
+        request_type: "RequestSetWindowFullscreen",
+        optional_fields: &[],
+    },
+    ToolDef {
+        name: "set_maximized",
+        description: "Maximize (maximized=true) or restore (maximized=false) a window. Returns the updated window properties, or an error if the window does not support the change.",
+        request_type: "RequestSetWindowMaximized",
+        optional_fields: &[],
+    },
+    ToolDef {
+        name: "set_minimized",
+        description: "Minimize (minimized=true) or restore (minimized=false) a window. Returns the updated window properties, or an error if the window does not support the change. A minimized window may not be rendered, so take_screenshot can return an empty or stale image until it is restored.",
+        request_type: "RequestSetWindowMinimized",
+        optional_fields: &[],
+    },
+    ToolDef {
+        name: "list_element_ids",
+        description: "List the IDs of all elements in a window that have one, with their handles, as a compact index of addressable elements. Returns {elements: [{id, handle}], totalCount, truncated} in tree order. IDs are qualified ('ComponentName::element-id') and can be passed to find_elements_by_id or wait_for_element later. maxElements limits the result (default: 1000, max: 10000); truncated is true if there are more.",
+        request_type: "RequestListElementIds",
+        optional_fields: &["maxElements"],
+    },
     ToolDef {
         name: "get_element_tree",
-        description: "Get a flat list of elements in the subtree rooted at the given element. Each entry includes type names, IDs, accessibility properties, geometry, and a handle for further queries. Use maxElements to control the result size (default: 200, max: 1000). If truncated is true, there are more elements — use query_element_descendants for targeted searches instead.",
+        description: "Get a flat list of elements in the subtree rooted at the given element. Each entry includes type names, IDs, accessibility properties, geometry, a handle for further queries, and the parentHandle of the closest listed ancestor (absent for the given element), from which the hierarchy can be rebuilt. Entries are in tree order, so every parent comes before its children. Use maxElements to control the result size (default: 200, max: 1000). If truncated is true, there are more elements — use query_element_descendants for targeted searches instead. For a more compact output format pass streaming=true: the result is then newline-delimited JSON with one compact element record per line (same fields as the elements entries, including handle and parentHandle), followed by a final {totalCount, truncated} line. It's still returned as a single response, so the same maxElements limit applies. To shrink the result further, pass fields with the property keys to keep per element, in camelCase or snake_case (e.g. [\"typeNamesAndIds\", \"accessibleRole\"]; unknown keys are an error; handle and parentHandle are always included; keys holding default values are omitted either way), and includeInvisible=false to skip descendants with zero size or zero opacity.",
         request_type: "RequestGetElementTree",
-        optional_fields: &["maxElements"],
+        optional_fields: &["maxElements", "streaming", "fields", "includeInvisible"],
     },
     ToolDef {
         name: "get_element_properties",
-        description: "Get full details of a single element: type names and IDs (including inherited bases), all accessible properties (role, label, value, description, checked, enabled, read-only, placeholder, value min/max/step), logical size and position, computed opacity, and layout kind.",
+        description: "Get full details of a single element: type names and IDs (including inherited bases), all accessible properties (role, label, value, description, checked, enabled, read-only, placeholder, value min/max/step), logical size and position, computed opacity, layout kind, the accessibility actions the element supports (supportedActions), and its stacking: siblingIndex among the children of its parent (absent for a window root) and zOrder, its position in the paint order (elements with a higher zOrder are drawn on top). Use zOrder to tell which of several overlapping elements is visible.",
         request_type: "RequestElementProperties",
         optional_fields: &[],
     },
+    ToolDef {
+        name: "assert_element_property",
+        description: "Check a property of an element, for self-checking tests. property is a get_element_properties field in snake_case or camelCase: accessible_label, accessible_value, accessible_value_minimum, accessible_value_maximum, accessible_value_step, accessible_description, accessible_placeholder_text, accessible_checked, accessible_checkable, accessible_enabled, accessible_read_only, accessible_role, computed_opacity, x, y, width and height. operator is eq, neq, contains, gt or lt, and expected is always a string. Boolean properties support eq/neq against \"true\"/\"false\"; numeric properties compare as numbers; text properties compare as strings, contains checks for a substring, and gt/lt compare numerically if both sides are numbers (e.g. a slider's accessible_value). Returns {passed, property, operator, actual, expected}; the result is marked as an error if the assertion fails.",
+        request_type: "RequestAssertElementProperty",
+        optional_fields: &[],
+    },
+    ToolDef {
+        name: "poll_until",
+        description: "Wait until a property of an element satisfies a condition, e.g. until a label changes after a click. Takes the same property, operator and expected arguments as assert_element_property and checks them every intervalMs (default 100) until the condition holds or timeoutMs (default 5000, max 60000) elapses. Returns {passed, polls, elapsedMs, actual} once the condition holds, or a timeout error that includes the last actual value.",
+        request_type: "RequestPollUntil",
+        optional_fields: &["timeoutMs", "intervalMs"],
+    },
+    ToolDef {
+        name: "get_element_properties_batch",
+        description: "Get the same details as get_element_properties, except zOrder, for several elements in one call. Returns an array with one {handle, properties} entry per requested handle, in order. A handle that is invalid or refers to a destroyed element yields {handle, error} instead of failing the whole batch.",
+        request_type: "RequestElementPropertiesBatch",
+        optional_fields: &[],
+    },
     ToolDef {
         name: "find_elements_by_id",
-        description: "Find elements by qualified ID (format: 'ComponentName::element-id', e.g. 'App::my-button'). Returns element handles. Use get_element_tree first to discover available IDs.",
+        description: "Find elements by qualified ID (format: 'ComponentName::element-id', e.g. 'App::my-button'). Returns {elementHandles, total, nextOffset}. Use get_element_tree or list_element_ids first to discover available IDs. For many matches, page through them with offset (default 0) and limit (default: all): total is the number of all matches, and nextOffset, if present, is the offset of the next page.",
         request_type: "RequestFindElementsById",
+        optional_fields: &["offset", "limit"],
+    },
+    ToolDef {
+        name: "find_elements_by_role",
+        description: "Find all elements in a window with the given accessible role (PascalCase, e.g. 'Button', 'TextInput', 'Checkbox', 'Slider'). Returns element handles. A shortcut for a recursive query_element_descendants from the window's root element.",
+        request_type: "RequestFindElementsByRole",
         optional_fields: &[],
     },
+    ToolDef {
+        name: "wait_for_element",
+        description: "Wait until at least one element with the given qualified ID (format: 'ComponentName::element-id') exists in the window, polling every 100ms. Returns the element handles as soon as they appear, or an error once timeoutMs (default: 5000, max: 60000) has elapsed. Use after interactions that show elements asynchronously, e.g. after an animation or a data load.",
+        request_type: "RequestWaitForElement",
+        optional_fields: &["timeoutMs"],
+    },
     ToolDef {
         name: "query_element_descendants",
-        description: "Search descendants of an element using a query pipeline. Pass an array of instructions applied in order: {\"matchDescendants\": true} to recurse, then filter by {\"matchElementId\": \"...\"}, {\"matchElementTypeName\": \"...\"}, {\"matchElementTypeNameOrBase\": \"...\"}, or {\"matchElementAccessibleRole\": \"Button\"}. More efficient than get_element_tree for targeted lookups.",
+        description: "Search descendants of an element using a query pipeline. Pass an array of instructions applied in order: {\"matchDescendants\": true} to recurse, then filter by {\"matchElementId\": \"...\"}, {\"matchElementTypeName\": \"...\"}, {\"matchElementTypeNameOrBase\": \"...\"}, or {\"matchElementAccessibleRole\": \"Button\"}. To accept several alternatives at one step, use {\"matchAnyOf\": {\"alternatives\": [{\"matchElementAccessibleRole\": \"Button\"}, {\"matchElementAccessibleRole\": \"Checkbox\"}]}}; each alternative is a matchElementTypeName, matchElementTypeNameOrBase or matchElementAccessibleRole. More efficient than get_element_tree for targeted lookups. Returns {elementHandles, total, nextOffset}; page through many matches with offset (default 0) and limit (default: all), where total counts all matches and nextOffset, if present, is the offset of the next page.",
         request_type: "RequestQueryElementDescendants",
-        optional_fields: &["findAll"],
+        optional_fields: &["findAll", "offset", "limit"],
+    },
+    ToolDef {
+        name: "get_element_at_position",
+        description: "Find the topmost element at a position in the window (logical coordinates, e.g. taken from a screenshot divided by the window's scale factor). The deepest element wins over its ancestors, and among overlapping siblings the one painted last wins. Elements with zero opacity and parts of elements clipped away by an ancestor (e.g. scrolled out of a Flickable) are not hit. Returns {handle, properties} with the same properties as get_element_properties, or null if no element contains the position.",
+        request_type: "RequestElementAtPosition",
+        optional_fields: &[],
+    },
+    ToolDef {
+        name: "get_focused_element",
+        description: "Get the element that currently has keyboard focus in a window. Returns {handle, properties} with the same properties as get_element_properties, or null if nothing is focused. Use it to verify Tab navigation driven through dispatch_key_event or dispatch_key_sequence.",
+        request_type: "RequestFocusedElement",
+        optional_fields: &[],
+    },
+    ToolDef {
+        name: "get_window_text",
+        description: "Get all visible text in a window in reading order (top to bottom, then left to right). Collects the accessible label and value of every element with an accessible role, skipping elements with zero size or zero opacity. Returns {text, items}: text joins all entries with newlines, and items lists {text, handle, position} for each entry. Useful for accessibility audits and for checking what is on screen without a screenshot.",
+        request_type: "RequestWindowText",
+        optional_fields: &[],
+    },
+    ToolDef {
+        name: "get_clipboard",
+        description: "Read the text on the clipboard, through the application's platform integration. On desktop platforms this is the system clipboard, shared with other applications. Set selection=true to read the selection clipboard (the primary selection on X11) instead. Returns {text}, where text is null if the clipboard holds no text or the platform has no clipboard.",
+        request_type: "RequestGetClipboard",
+        optional_fields: &["selection"],
+    },
+    ToolDef {
+        name: "set_clipboard",
+        description: "Put text on the clipboard, through the application's platform integration. On desktop platforms this replaces the contents of the system clipboard, shared with other applications. Combine with dispatch_key_event (Ctrl+V, or Cmd+V on macOS) on a focused text input to test pasting. Set selection=true to write the selection clipboard (the primary selection on X11) instead. Returns {applied}, which is false if reading the clipboard back does not give the text, e.g. because the platform has no clipboard.",
+        request_type: "RequestSetClipboard",
+        optional_fields: &["selection"],
     },
     ToolDef {
         name: "take_screenshot",
-        description: "Capture a PNG screenshot of a window. Returns an MCP image content block rendered inline by the client. Use after interactions to verify visual results.",
+        description: "Capture a screenshot of a window. Returns an MCP image content block rendered inline by the client. Use after interactions to verify visual results. imageMimeType selects the format: \"png\" (default), \"jpeg\" (smaller, lossy) or \"webp\" (lossless); MIME types such as \"image/jpeg\" are accepted as well. Pass elementHandle to crop the screenshot to that element's bounds (clamped to the window). quality (1-100, clamped) trades size for fidelity with \"jpeg\" and is ignored for the lossless formats. The response reports the encoded sizeBytes.",
         request_type: "RequestTakeSnapshot",
-        optional_fields: &["imageMimeType"],
+        optional_fields: &["imageMimeType", "elementHandle", "quality"],
+    },
+    ToolDef {
+        name: "save_screenshot",
+        description: "Capture a screenshot like take_screenshot, but write it to a file on the application's machine instead of returning the image data. Use this to keep large images out of the conversation while still being able to reference them. Files are written to the directory set by SLINT_MCP_SCREENSHOT_DIR (default: slint-mcp-screenshots in the system temporary directory). fileName is a plain file name (letters, digits, '.', '-' and '_'; no directories); if omitted, a name is generated. The extension matching imageMimeType is appended if missing. Returns {path, width, height, sizeBytes, mimeType}.",
+        request_type: "RequestSaveScreenshot",
+        optional_fields: &["imageMimeType", "elementHandle", "quality", "fileName"],
+    },
+    ToolDef {
+        name: "compare_screenshots",
+        description: "Compare two images pixel by pixel for visual regression checks. baseline is a base64 encoded image (PNG, JPEG or WebP); alternatively pass baselinePath to load a stored baseline: the file name (no directories) of an image in the SLINT_MCP_SCREENSHOT_DIR directory, e.g. one written by save_screenshot. candidate is a base64 encoded image; if omitted, windowHandle is captured instead. A pixel differs if any RGBA channel differs by more than tolerance (0-255, default 0). Returns {sizeMatches, width, height, differingPixels, totalPixels, similarity} where similarity is the fraction of equal pixels. If the sizes differ, returns sizeMatches=false with both sizes instead. Set includeDiffImage to also get a PNG with the differing pixels in red over a dimmed baseline.",
+        request_type: "RequestCompareScreenshots",
+        optional_fields: &[
+            "baseline",
+            "baselinePath",
+            "candidate",
+            "windowHandle",
+            "tolerance",
+            "includeDiffImage",
+        ],
     },
     ToolDef {
         name: "click_element",
@@ -90,6 +230,18 @@ struct ToolDef {
         request_type: "RequestElementClick",
         optional_fields: &["action", "button"],
     },
+    ToolDef {
+        name: "drag_element_to",
+        description: "Drag-and-drop: press at the center of elementHandle, move the pointer in `steps` equal increments (default: 10, max: 1000, 16ms apart) and release over the drop target. The target is either targetElementHandle (its center) or targetPosition (logical window coordinates); pass exactly one. Use more steps for long distances or when the app reacts to intermediate moves. Returns the start and end positions.",
+        request_type: "RequestElementDragTo",
+        optional_fields: &["targetElementHandle", "targetPosition", "steps", "button"],
+    },
+    ToolDef {
+        name: "hover_element",
+        description: "Move the mouse pointer over the element's center without pressing a button, so hover-dependent UI (tooltips, highlights, TouchArea has-hover) shows up — e.g. before taking a screenshot. Returns the logical position the pointer was moved to.",
+        request_type: "RequestElementHover",
+        optional_fields: &[],
+    },
     ToolDef {
         name: "drag_element",
         description: "Simulate a drag gesture from the element's center to a target position (logical coordinates). The pointer is pressed at the element center, moved in interpolated steps to the target, then released. Use for sliders, scrollable areas, drag handles, or any element that responds to pointer movement while pressed.",
@@ -98,7 +250,7 @@ struct ToolDef {
     },
     ToolDef {
         name: "invoke_accessibility_action",
-        description: "Invoke an accessibility action: 'Default_' (activate buttons, toggle checkboxes), 'Increment'/'Decrement' (sliders, spinboxes), 'Expand' (combo boxes). Preferred over click_element when the element's role suggests a semantic action.",
+        description: "Invoke an accessibility action: 'Default_' (activate buttons, toggle checkboxes), 'Increment'/'Decrement' (sliders, spinboxes), 'Expand' (combo boxes). Only actions listed in the element's supportedActions property have an effect. To set a value directly, use set_element_value instead. Preferred over click_element when the element's role suggests a semantic action.",
         request_type: "RequestInvokeElementAccessibilityAction",
         optional_fields: &[],
     },
@@ -114,6 +266,18 @@ struct ToolDef {
         request_type: "RequestDispatchKeyEvent",
         optional_fields: &["eventType"],
     },
+    ToolDef {
+        name: "dispatch_key_sequence",
+        description: "Send a sequence of key presses to a window. Each step has a key — either text to type or a named key (Enter, Tab, Escape, Backspace, Delete, ArrowLeft/Right/Up/Down, Home, End, PageUp, PageDown, F1..F24, ...) — and optional modifiers (\"ctrl\", \"shift\", \"alt\", \"meta\") that are held down while the key is pressed. Example: [{\"key\": \"a\", \"modifiers\": [\"ctrl\"]}, {\"key\": \"Delete\"}] selects all and deletes it.",
+        request_type: "RequestDispatchKeySequence",
+        optional_fields: &[],
+    },
+    ToolDef {
+        name: "close_window",
+        description: "Request a window to close, as if the user clicked its close button. The application's close-requested callback runs first and may veto the request, e.g. to show an \"unsaved changes\" dialog. Returns {closed: true} if the window was hidden, or {closed: false} if the application kept it shown.",
+        request_type: "RequestCloseWindow",
+        optional_fields: &[],
+    },
     ToolDef {
         name: "start_event_recording",
         description: "Clear the event log and begin recording window/input events. Call this before the interaction you want to observe, then call stop_event_recording when done.",
@@ -140,12 +304,16 @@ fn handle_field_description(field_name: &str) -> Option<&'static str> {
              Window and element handles look identical but are not interchangeable: \
              passing an element handle here is incorrect.",
         ),
-        "elementHandle" => Some(
+        "elementHandle" | "targetElementHandle" => Some(
             "An element handle (NOT a window handle). Obtain it from get_element_tree, \
              find_elements_by_id, query_element_descendants, or a window's rootElementHandle \
              (from get_window_properties). Window and element handles look identical but are \
              not interchangeable: passing a window handle here is incorrect.",
         ),
+        "elementHandles" => Some(
+            "Element handles (NOT window handles), as returned by get_element_tree, \
+             find_elements_by_id or query_element_descendants.",
+        ),
         _ => None,
     }
 }
@@ -206,21 +374,692 @@ fn tool_definitions() -> Value {
 // Tool dispatch
 // ============================================================================
 
-fn deserialize_params<T: serde::de::DeserializeOwned>(args: &Value) -> Result<T, String> {
-    serde_json::from_value(args.clone()).map_err(|e| format!("Invalid parameters: {e}"))
+/// Looks up a named key (e.g. "Enter", "ArrowLeft") by its Slint name or its
+/// [W3C name](https://www.w3.org/TR/uievents-key/#named-key-attribute-values).
+fn named_key(name: &str) -> Option<char> {
+    macro_rules! named_key_impl {
+        ($($char:literal # $name:ident # $($_shifted:ident)? $(=> $($w3c:ident)? # $($_qt:ident)|* # $($_winit:ident $(($_pos:ident))?)|* # $($_xkb:ident)|*)?;)*) => {
+            match name {
+                $(stringify!($name) $($(| stringify!($w3c))?)? => Some($char),)*
+                _ => None,
+            }
+        };
+    }
+    i_slint_common::for_each_keys!(named_key_impl)
+}
+
+fn modifier_key(name: &str) -> Result<char, String> {
+    use i_slint_core::input::key_codes;
+    match name.to_ascii_lowercase().as_str() {
+        "ctrl" | "control" => Ok(key_codes::Control),
+        "shift" => Ok(key_codes::Shift),
+        "alt" => Ok(key_codes::Alt),
+        "meta" => Ok(key_codes::Meta),
+        _ => Err(format!(
+            "unknown modifier '{name}': expected \"ctrl\", \"shift\", \"alt\" or \"meta\""
+        )),
+    }
+}
+
+/// Expands key sequence steps into window events: each step presses its modifiers,
+/// presses and releases the key, then releases the modifiers in reverse order.
+fn key_sequence_events(
+    steps: &[proto::KeySequenceStep],
+) -> Result<Vec<i_slint_core::platform::WindowEvent>, String> {
+    use i_slint_core::platform::WindowEvent;
+    let mut events = Vec::new();
+    for (i, step) in steps.iter().enumerate() {
+        if step.key.is_empty() {
+            return Err(format!("step {i}: key must not be empty"));
+        }
+        let modifiers = step
+            .modifiers
+            .iter()
+            .map(|m| modifier_key(m).map_err(|e| format!("step {i}: {e}")))
+            .collect::<Result<Vec<_>, _>>()?;
+        let text: i_slint_core::SharedString = match named_key(&step.key) {
+            Some(key) => key.into(),
+            None => step.key.as_str().into(),
+        };
+        events.extend(modifiers.iter().map(|m| WindowEvent::KeyPressed { text: (*m).into() }));
+        events.push(WindowEvent::KeyPressed { text: text.clone() });
+        events.push(WindowEvent::KeyReleased { text });
+        events
+            .extend(modifiers.iter().rev().map(|m| WindowEvent::KeyReleased { text: (*m).into() }));
+    }
+    Ok(events)
+}
+
+fn deserialize_params<T: serde::de::DeserializeOwned>(args: &Value) -> Result<T, ToolError> {
+    serde_json::from_value(args.clone())
+        .map_err(|e| ToolError::bad_argument(format!("Invalid parameters: {e}")))
+}
+
+/// Machine-readable category of a failed tool call, reported in `structuredContent`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum ToolErrorCode {
+    /// The arguments are missing, malformed, or out of range.
+    BadArgument,
+    /// A window or element handle doesn't (or no longer) refer to a live window or element.
+    StaleHandle,
+    /// The awaited condition didn't occur in time.
+    Timeout,
+    /// Anything else, e.g. a failure inside the application.
+    Unexpected,
+}
+
+impl ToolErrorCode {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::BadArgument => "bad_argument",
+            Self::StaleHandle => "stale_handle",
+            Self::Timeout => "timeout",
+            Self::Unexpected => "unexpected",
+        }
+    }
+}
+
+#[derive(Debug)]
+struct ToolError {
+    code: ToolErrorCode,
+    message: String,
+}
+
+impl ToolError {
+    fn bad_argument(message: impl Into<String>) -> Self {
+        Self { code: ToolErrorCode::BadArgument, message: message.into() }
+    }
+
+    fn stale_handle(message: impl Into<String>) -> Self {
+        Self { code: ToolErrorCode::StaleHandle, message: message.into() }
+    }
+}
+
+impl From<String> for ToolError {
+    fn from(message: String) -> Self {
+        Self { code: ToolErrorCode::Unexpected, message }
+    }
+}
+
+/// A proto enum taken as a tool argument, with uniform parsing and error messages.
+trait EnumArgument: Copy + TryFrom<i32> {
+    /// The name of the argument in error messages.
+    const ARGUMENT: &'static str;
+
+    fn name(self) -> &'static str;
+
+    /// All values, in the order of their numbers (which are contiguous from zero).
+    fn values() -> impl Iterator<Item = Self> {
+        (0..).map_while(|i| Self::try_from(i).ok())
+    }
+
+    /// Parses a value by name, ignoring case and underscores, so that e.g. `left` is accepted
+    /// for `Left` and `default` for `Default_`.
+    fn from_name(name: &str) -> Result<Self, String> {
+        let key = |name: &str| -> String {
+            name.chars().filter(|c| *c != '_').map(|c| c.to_ascii_lowercase()).collect()
+        };
+        let wanted = key(name);
+        Self::values()
+            .find(|value| key(value.name()) == wanted)
+            .ok_or_else(|| unknown_enum_value::<Self>(name))
+    }
+
+    fn from_number(number: i32) -> Result<Self, String> {
+        Self::try_from(number).map_err(|_| unknown_enum_value::<Self>(&number.to_string()))
+    }
+}
+
+fn unknown_enum_value<E: EnumArgument>(value: &str) -> String {
+    let names: Vec<_> = E::values().map(E::name).collect();
+    format!("unknown {} '{value}'; expected one of {}", E::ARGUMENT, names.join(", "))
+}
+
+impl EnumArgument for proto::PointerEventButton {
+    const ARGUMENT: &'static str = "button";
+    fn name(self) -> &'static str {
+        self.as_str_name()
+    }
+}
+
+impl EnumArgument for proto::ClickAction {
+    const ARGUMENT: &'static str = "action";
+    fn name(self) -> &'static str {
+        self.as_str_name()
+    }
+}
+
+impl EnumArgument for proto::ElementAccessibilityAction {
+    const ARGUMENT: &'static str = "action";
+    fn name(self) -> &'static str {
+        self.as_str_name()
+    }
+}
+
+impl EnumArgument for proto::KeyEventType {
+    const ARGUMENT: &'static str = "eventType";
+    fn name(self) -> &'static str {
+        self.as_str_name()
+    }
+}
+
+/// Replaces an enum argument given by name with its canonical proto name, so that the
+/// deserializer accepts it. Numbers are left for [`EnumArgument::from_number`].
+fn canonicalize_enum_argument<E: EnumArgument>(
+    args: &mut Value,
+    field: &str,
+) -> Result<(), String> {
+    if let Some(value) = args.get_mut(field)
+        && let Some(name) = value.as_str()
+    {
+        *value = E::from_name(name)?.name().into();
+    }
+    Ok(())
+}
+
+/// Canonicalizes the enum arguments of the tool `tool`, see [`canonicalize_enum_argument`].
+fn canonicalize_enum_arguments(tool: &str, args: &Value) -> Result<Value, String> {
+    let mut args = args.clone();
+    match tool {
+        "click_element" => {
+            canonicalize_enum_argument::<proto::ClickAction>(&mut args, "action")?;
+            canonicalize_enum_argument::<proto::PointerEventButton>(&mut args, "button")?;
+        }
+        "drag_element" | "drag_element_to" => {
+            canonicalize_enum_argument::<proto::PointerEventButton>(&mut args, "button")?;
+        }
+        "invoke_accessibility_action" => {
+            canonicalize_enum_argument::<proto::ElementAccessibilityAction>(&mut args, "action")?;
+        }
+        "dispatch_key_event" => {
+            canonicalize_enum_argument::<proto::KeyEventType>(&mut args, "eventType")?;
+        }
+        _ => {}
+    }
+    Ok(args)
+}
+
+/// Resolves a `windowHandle` argument, distinguishing a missing or malformed handle
+/// from one that doesn't refer to a live window.
+fn window_arg(
+    state: &IntrospectionState,
+    handle: Option<proto::Handle>,
+) -> Result<introspection::ArenaIndex, ToolError> {
+    let handle = handle.ok_or_else(|| ToolError::bad_argument("missing windowHandle"))?;
+    let index = handle_to_index(handle).map_err(ToolError::bad_argument)?;
+    state.window_adapter(index).map_err(ToolError::stale_handle)?;
+    Ok(index)
+}
+
+/// Like [`window_arg`], for an `elementHandle` argument of the tool `tool`.
+fn element_arg(
+    state: &IntrospectionState,
+    tool: &str,
+    handle: Option<proto::Handle>,
+) -> Result<introspection::ArenaIndex, ToolError> {
+    let handle = handle.ok_or_else(|| ToolError::bad_argument("missing elementHandle"))?;
+    let index = handle_to_index(handle).map_err(ToolError::bad_argument)?;
+    state.element(tool, index).map_err(ToolError::stale_handle)?;
+    Ok(index)
+}
+
+/// Describes what is wrong with the value of a handle's `index` or `generation` field,
+/// or returns `None` if it is a valid uint64 (as a number or, per the protobuf JSON
+/// convention, a decimal string).
+fn handle_integer_error(value: &Value) -> Option<&'static str> {
+    let (is_u64, is_i64, is_f64) = match value {
+        Value::Number(n) => (n.is_u64(), n.is_i64(), n.is_f64()),
+        Value::String(s) => {
+            (s.parse::<u64>().is_ok(), s.parse::<i64>().is_ok(), s.parse::<f64>().is_ok())
+        }
+        _ => (false, false, false),
+    };
+    if is_u64 {
+        None
+    } else if is_i64 {
+        Some("must be a non-negative integer")
+    } else if is_f64 {
+        Some("must be an integer, not a fractional number")
+    } else {
+        Some("must be an integer (as a number or a decimal string)")
+    }
+}
+
+/// Checks the `index` and `generation` fields of all handle arguments (`*Handle` and
+/// `*Handles`) up front, so that malformed values produce a specific error rather than
+/// the generic serde message or a confusing lookup failure.
+fn validate_handle_arguments(args: &Value) -> Result<(), String> {
+    let Some(args) = args.as_object() else {
+        return Ok(());
+    };
+    for (name, value) in args {
+        let handles: Vec<&Value> = if name.ends_with("Handle") {
+            vec![value]
+        } else if name.ends_with("Handles") {
+            value.as_array().map(|handles| handles.iter().collect()).unwrap_or_default()
+        } else {
+            continue;
+        };
+        for handle in handles {
+            if handle.is_null() {
+                continue;
+            }
+            let Some(handle) = handle.as_object() else {
+                return Err(format!(
+                    "{name} must be an object with index and generation fields, got {handle}"
+                ));
+            };
+            for field in ["index", "generation"] {
+                // Zero-valued fields may be omitted.
+                let Some(value) = handle.get(field) else { continue };
+                if let Some(error) = handle_integer_error(value) {
+                    return Err(format!("{name} {field} {error}, got {value}"));
+                }
+            }
+        }
+    }
+    Ok(())
 }
 
-/// Tool call result: either a JSON value (rendered as text) or an image with optional metadata.
+/// Tool call result: a JSON value (rendered as pretty-printed text), preformatted text,
+/// or an image with optional metadata. `Failed` is a JSON value for a check that ran but
+/// did not pass, reported with `isError`.
 enum ToolResult {
     Json(Value),
-    Image { png_data: Vec<u8>, meta: Value },
+    Failed(Value),
+    Text(String),
+    Image { data: Vec<u8>, mime_type: &'static str, meta: Value },
+}
+
+/// A value of get_element_properties, as compared by assert_element_property.
+#[derive(Clone, Debug, PartialEq)]
+enum PropertyValue {
+    Bool(bool),
+    Number(f64),
+    Text(String),
+}
+
+impl PropertyValue {
+    fn to_json(&self) -> Value {
+        match self {
+            Self::Bool(value) => (*value).into(),
+            Self::Number(value) => (*value).into(),
+            Self::Text(value) => value.as_str().into(),
+        }
+    }
+}
+
+/// Looks up a property by its snake_case or camelCase name, or `None` if there's no such
+/// property. Enums are reported by name, and `x`/`y`/`width`/`height` are broken out of the
+/// absolute position and size.
+fn element_property(
+    properties: &proto::ElementPropertiesResponse,
+    name: &str,
+) -> Option<PropertyValue> {
+    use PropertyValue::*;
+    let name: String = name.chars().filter(|c| *c != '_').map(|c| c.to_ascii_lowercase()).collect();
+    let position = properties.absolute_position.unwrap_or_default();
+    let size = properties.size.unwrap_or_default();
+    Some(match name.as_str() {
+        "accessiblelabel" => Text(properties.accessible_label.clone()),
+        "accessiblevalue" => Text(properties.accessible_value.clone()),
+        "accessiblevalueminimum" => Number(properties.accessible_value_minimum.into()),
+        "accessiblevaluemaximum" => Number(properties.accessible_value_maximum.into()),
+        "accessiblevaluestep" => Number(properties.accessible_value_step.into()),
+        "accessibledescription" => Text(properties.accessible_description.clone()),
+        "accessibleplaceholdertext" => Text(properties.accessible_placeholder_text.clone()),
+        "accessiblechecked" => Bool(properties.accessible_checked),
+        "accessiblecheckable" => Bool(properties.accessible_checkable),
+        "accessibleenabled" => Bool(properties.accessible_enabled),
+        "accessiblereadonly" => Bool(properties.accessible_read_only),
+        "accessiblerole" => Text(
+            proto::AccessibleRole::try_from(properties.accessible_role)
+                .map_or("Unknown", |role| role.as_str_name())
+                .into(),
+        ),
+        "computedopacity" => Number(properties.computed_opacity.into()),
+        "x" => Number(position.x.into()),
+        "y" => Number(position.y.into()),
+        "width" => Number(size.width.into()),
+        "height" => Number(size.height.into()),
+        _ => return None,
+    })
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum PropertyOperator {
+    Eq,
+    Neq,
+    Contains,
+    Gt,
+    Lt,
+}
+
+impl std::str::FromStr for PropertyOperator {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, String> {
+        match s {
+            "eq" => Ok(Self::Eq),
+            "neq" => Ok(Self::Neq),
+            "contains" => Ok(Self::Contains),
+            "gt" => Ok(Self::Gt),
+            "lt" => Ok(Self::Lt),
+            _ => Err(format!(
+                "unknown operator '{s}': expected \"eq\", \"neq\", \"contains\", \"gt\" or \"lt\""
+            )),
+        }
+    }
+}
+
+/// A check of one element property against an expected value.
+struct PropertyCondition {
+    property: String,
+    operator: PropertyOperator,
+    expected: String,
+}
+
+impl PropertyCondition {
+    fn new(property: &str, operator: &str, expected: &str) -> Result<Self, String> {
+        if element_property(&Default::default(), property).is_none() {
+            return Err(format!("unknown property '{property}'"));
+        }
+        let condition = Self {
+            property: property.into(),
+            operator: operator.parse()?,
+            expected: expected.into(),
+        };
+        // Check that the operator and expected value fit the type of the property up front,
+        // so that a malformed condition is reported as such and not as a failed check.
+        condition.check(&Default::default())?;
+        Ok(condition)
+    }
+
+    /// Returns whether the condition holds for `properties`, and the actual value.
+    fn check(
+        &self,
+        properties: &proto::ElementPropertiesResponse,
+    ) -> Result<(bool, PropertyValue), String> {
+        use PropertyOperator::*;
+        let actual = element_property(properties, &self.property)
+            .ok_or_else(|| format!("unknown property '{}'", self.property))?;
+        let expected = self.expected.as_str();
+        let number = |value: &str| {
+            value.trim().parse::<f64>().map_err(|_| {
+                format!(
+                    "property '{}' is numeric, but expected '{value}' is not a number",
+                    self.property
+                )
+            })
+        };
+        let passed = match (&actual, self.operator) {
+            (PropertyValue::Bool(actual), Eq | Neq) => {
+                let expected: bool = expected.parse().map_err(|_| {
+                    format!(
+                        "property '{}' is boolean, expected \"true\" or \"false\", not '{expected}'",
+                        self.property
+                    )
+                })?;
+                (*actual == expected) == (self.operator == Eq)
+            }
+            (PropertyValue::Number(actual), Eq) => *actual == number(expected)?,
+            (PropertyValue::Number(actual), Neq) => *actual != number(expected)?,
+            (PropertyValue::Number(actual), Gt) => *actual > number(expected)?,
+            (PropertyValue::Number(actual), Lt) => *actual < number(expected)?,
+            (PropertyValue::Text(actual), Eq) => actual == expected,
+            (PropertyValue::Text(actual), Neq) => actual != expected,
+            (PropertyValue::Text(actual), Contains) => actual.contains(expected),
+            (PropertyValue::Text(actual), Gt | Lt) => {
+                let expected = expected.trim().parse::<f64>().map_err(|_| {
+                    format!("gt and lt need a numeric expected value, not '{expected}'")
+                })?;
+                // An empty or non-numeric value (e.g. of a default-constructed property
+                // while validating the condition) never passes.
+                match actual.trim().parse::<f64>() {
+                    Ok(actual) if self.operator == Gt => actual > expected,
+                    Ok(actual) => actual < expected,
+                    Err(_) => false,
+                }
+            }
+            (PropertyValue::Bool(_), _) | (PropertyValue::Number(_), Contains) => {
+                return Err(format!(
+                    "operator {:?} is not supported for property '{}'",
+                    self.operator, self.property
+                ));
+            }
+        };
+        Ok((passed, actual))
+    }
+}
+
+/// Decodes an image argument of compare_screenshots into RGBA pixels.
+fn decode_image(data: &[u8], field: &str) -> Result<image::RgbaImage, ToolError> {
+    image::load_from_memory(data)
+        .map(|image| image.to_rgba8())
+        .map_err(|e| ToolError::bad_argument(format!("cannot decode {field} image: {e}")))
+}
+
+/// Checks that `name` only consists of letters, digits, '.', '-' and '_' and doesn't start
+/// with '.', so that it can't be absolute, contain `..` or otherwise escape the screenshot
+/// directory.
+fn check_plain_file_name(name: &str, field: &str) -> Result<(), String> {
+    if name.starts_with('.')
+        || !name.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_'))
+    {
+        return Err(format!(
+            "invalid {field} '{name}': expected a plain file name of letters, digits, '.', '-' and '_'"
+        ));
+    }
+    Ok(())
+}
+
+/// Resolves the `baselinePath` argument of compare_screenshots to a file in the screenshot
+/// directory, which must exist.
+fn screenshot_baseline_path(name: &str) -> Result<std::path::PathBuf, String> {
+    check_plain_file_name(name, "baselinePath")?;
+    let dir = SCREENSHOT_DIR.with(|dir| dir.borrow().clone());
+    if dir.as_os_str().is_empty() || !dir.is_dir() {
+        return Err(format!(
+            "no screenshot directory {}: save a baseline with save_screenshot first, or set SLINT_MCP_SCREENSHOT_DIR",
+            dir.display()
+        ));
+    }
+    Ok(dir.join(name))
+}
+
+/// Checks that `name` is a plain file name for save_screenshot and adds the extension of
+/// `mime_type` if it's missing. An empty name is replaced by one derived from the current time.
+fn screenshot_file_name(name: &str, mime_type: &str) -> Result<String, String> {
+    let extension = image::ImageFormat::from_mime_type(mime_type)
+        .and_then(|format| format.extensions_str().first().copied())
+        .unwrap_or("png");
+    let name = if name.is_empty() {
+        let millis = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |duration| duration.as_millis());
+        format!("screenshot-{millis}")
+    } else {
+        check_plain_file_name(name, "fileName")?;
+        name.to_string()
+    };
+    let has_extension = std::path::Path::new(&name)
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case(extension));
+    Ok(if has_extension { name } else { format!("{name}.{extension}") })
+}
+
+/// Maps the `imageMimeType` argument of take_screenshot to the MIME type to encode with.
+/// Accepts the bare format names as well as MIME types; empty means PNG.
+fn screenshot_mime_type(requested: &str) -> Result<&'static str, String> {
+    match requested.to_ascii_lowercase().as_str() {
+        "" | "png" | "image/png" => Ok("image/png"),
+        "jpeg" | "jpg" | "image/jpeg" => Ok("image/jpeg"),
+        "webp" | "image/webp" => Ok("image/webp"),
+        _ => Err(format!(
+            "unsupported screenshot format '{requested}': expected \"png\", \"jpeg\" or \"webp\""
+        )),
+    }
+}
+
+/// Walks the subtree rooted at `root` in tree order, root included, and passes the projections
+/// of its elements to `emit`, skipping those that project to `None`, up to `max_elements`.
+/// Along with each projection, `emit` gets the `handle` of the nearest ancestor that was emitted
+/// before, if any. Returns the number of emitted elements and whether elements were left out
+/// because of the limit.
+fn collect_subtree(
+    root: &crate::ElementHandle,
+    include_invisible: bool,
+    max_elements: usize,
+    mut project: impl FnMut(&crate::ElementHandle) -> Option<Value>,
+    mut emit: impl FnMut(Value, Option<&Value>),
+) -> (usize, bool) {
+    // The path from `root` to the element being visited, each with the handle of the closest
+    // emitted element at or above it.
+    let mut path: Vec<(crate::ElementHandle, Option<Value>)> = Vec::new();
+    let mut count = 0;
+    let mut truncated = false;
+    let mut visit = |element: &crate::ElementHandle, listed: bool| {
+        if !path.is_empty() {
+            // Elements are visited depth first, so the elements above the parent on the path
+            // belong to subtrees that were left. Look further up if the parent isn't on the
+            // path, as visit_descendants skips items that are clipped away entirely, and fall
+            // back to the root.
+            let mut parent = element.parent_element();
+            let mut depth = 1;
+            while let Some(candidate) = parent {
+                if let Some(index) = path.iter().rposition(|(e, _)| e.is_same_element(&candidate)) {
+                    depth = index + 1;
+                    break;
+                }
+                parent = candidate.parent_element();
+            }
+            path.truncate(depth);
+        }
+        let closest = path.last().and_then(|(_, handle)| handle.clone());
+        let node = if listed { project(element) } else { None };
+        let handle = match node {
+            Some(_) if count >= max_elements => {
+                truncated = true;
+                return std::ops::ControlFlow::Break(());
+            }
+            Some(node) => {
+                let handle = node["handle"].clone();
+                emit(node, closest.as_ref());
+                count += 1;
+                Some(handle)
+            }
+            None => closest,
+        };
+        path.push((element.clone(), handle));
+        std::ops::ControlFlow::Continue(())
+    };
+    let _ = visit(root, true);
+    root.visit_descendants(|child| {
+        let listed = include_invisible || !introspection::is_invisible(&child);
+        visit(&child, listed)
+    });
+    (count, truncated)
+}
+
+/// Returns the handle of `element` in the subtree of `root`, reusing `root_index` for the root
+/// itself instead of registering it once more.
+fn element_handle_in_subtree(
+    state: &IntrospectionState,
+    root: &crate::ElementHandle,
+    root_index: introspection::ArenaIndex,
+    element: &crate::ElementHandle,
+) -> introspection::ArenaIndex {
+    if element.is_same_element(root) {
+        root_index
+    } else {
+        state.element_to_handle(element.clone())
+    }
+}
+
+/// The property keys of an element in get_element_tree, which its `fields` argument selects from.
+const ELEMENT_TREE_FIELDS: &[&str] = &[
+    "typeNamesAndIds",
+    "accessibleLabel",
+    "accessibleValue",
+    "accessibleValueMaximum",
+    "accessibleValueMinimum",
+    "accessibleValueStep",
+    "accessibleDescription",
+    "accessibleChecked",
+    "accessibleCheckable",
+    "size",
+    "absolutePosition",
+    "accessibleRole",
+    "computedOpacity",
+    "accessiblePlaceholderText",
+    "accessibleEnabled",
+    "accessibleReadOnly",
+    "layoutKind",
+    "supportedActions",
+    "siblingIndex",
+];
+
+/// Maps the `fields` argument of get_element_tree, in snake_case or camelCase, to the
+/// camelCase property keys, or reports the first unknown one.
+fn element_tree_fields(fields: &[String]) -> Result<Vec<&'static str>, String> {
+    let normalize = |name: &str| -> String {
+        name.chars().filter(|c| *c != '_').map(|c| c.to_ascii_lowercase()).collect()
+    };
+    fields
+        .iter()
+        .map(|field| {
+            let normalized = normalize(field);
+            let key = ELEMENT_TREE_FIELDS.iter().find(|key| normalize(key) == normalized);
+            key.copied().ok_or_else(|| {
+                format!(
+                    "unknown field '{field}': expected one of {}",
+                    ELEMENT_TREE_FIELDS.join(", ")
+                )
+            })
+        })
+        .collect()
+}
+
+/// Serializes the properties of an element for get_element_tree, keeping only `fields` if
+/// non-empty, and adds its handle.
+fn element_tree_node(
+    element: &crate::ElementHandle,
+    handle: introspection::ArenaIndex,
+    fields: &[&str],
+) -> Result<Value, String> {
+    let mut node = serde_json::to_value(introspection::element_properties(element))
+        .map_err(|e| format!("serialize error: {e}"))?;
+    if let Some(obj) = node.as_object_mut() {
+        if !fields.is_empty() {
+            obj.retain(|key, _| fields.contains(&key.as_str()));
+        }
+        obj.insert(
+            "handle".to_string(),
+            serde_json::to_value(index_to_handle(handle))
+                .map_err(|e| format!("serialize error: {e}"))?,
+        );
+    }
+    Ok(node)
+}
+
+/// Result of the window geometry setters: the window properties after the change, plus
+/// whether the windowing system applied the requested value unchanged.
+fn window_properties_with_applied(
+    properties: proto::WindowPropertiesResponse,
+    applied: bool,
+) -> Result<ToolResult, ToolError> {
+    let mut result =
+        serde_json::to_value(properties).map_err(|e| format!("serialize error: {e}"))?;
+    result["applied"] = Value::Bool(applied);
+    Ok(ToolResult::Json(result))
 }
 
 async fn handle_tool_call(
     state: &IntrospectionState,
     name: &str,
     args: &Value,
-) -> Result<ToolResult, String> {
+) -> Result<ToolResult, ToolError> {
+    validate_handle_arguments(args).map_err(ToolError::bad_argument)?;
+    let args = &canonicalize_enum_arguments(name, args).map_err(ToolError::bad_argument)?;
     match name {
         "list_windows" => {
             let response = dispatch::list_windows(state);
@@ -228,46 +1067,245 @@ async fn handle_tool_call(
                 serde_json::to_value(response).map_err(|e| format!("serialize error: {e}"))?,
             ))
         }
-        "get_window_properties" => {
-            let p: proto::RequestWindowProperties = deserialize_params(args)?;
-            let window_index = handle_to_index(
-                p.window_handle.ok_or_else(|| "missing windowHandle".to_string())?,
+        "set_window_size" => {
+            let p: proto::RequestSetWindowSize = deserialize_params(args)?;
+            let window_index = window_arg(state, p.window_handle)?;
+            let size = p.size.ok_or_else(|| ToolError::bad_argument("missing size"))?;
+            let response = dispatch::set_window_size(state, window_index, size)?;
+            let applied = response.size == Some(size);
+            window_properties_with_applied(response, applied)
+        }
+        "set_window_position" => {
+            let p: proto::RequestSetWindowPosition = deserialize_params(args)?;
+            let window_index = window_arg(state, p.window_handle)?;
+            let position = p.position.ok_or_else(|| ToolError::bad_argument("missing position"))?;
+            let response = dispatch::set_window_position(state, window_index, position)?;
+            let applied = response.position == Some(position);
+            window_properties_with_applied(response, applied)
+        }
+        "set_fullscreen" => {
+            let p: proto::RequestSetWindowFullscreen = deserialize_params(args)?;
+            let window_index = window_arg(state, p.window_handle)?;
+            let response = dispatch::set_window_state(
+                state,
+                window_index,
+                introspection::WindowState::Fullscreen,
+                p.fullscreen,
             )?;
-            let response = dispatch::window_properties(state, window_index)?;
             Ok(ToolResult::Json(
                 serde_json::to_value(response).map_err(|e| format!("serialize error: {e}"))?,
             ))
         }
-        "find_elements_by_id" => {
-            let p: proto::RequestFindElementsById = deserialize_params(args)?;
-            let window_index = handle_to_index(
-                p.window_handle.ok_or_else(|| "missing windowHandle".to_string())?,
+        "set_maximized" => {
+            let p: proto::RequestSetWindowMaximized = deserialize_params(args)?;
+            let window_index = window_arg(state, p.window_handle)?;
+            let response = dispatch::set_window_state(
+                state,
+                window_index,
+                introspection::WindowState::Maximized,
+                p.maximized,
+            )?;
+            Ok(ToolResult::Json(
+                serde_json::to_value(response).map_err(|e| format!("serialize error: {e}"))?,
+            ))
+        }
+        "set_minimized" => {
+            let p: proto::RequestSetWindowMinimized = deserialize_params(args)?;
+            let window_index = window_arg(state, p.window_handle)?;
+            let response = dispatch::set_window_state(
+                state,
+                window_index,
+                introspection::WindowState::Minimized,
+                p.minimized,
+            )?;
+            Ok(ToolResult::Json(
+                serde_json::to_value(response).map_err(|e| format!("serialize error: {e}"))?,
+            ))
+        }
+        "ping" => {
+            let start = std::time::Instant::now();
+            let window_count = dispatch::list_windows(state).window_handles.len();
+            Ok(ToolResult::Json(serde_json::json!({
+                "connected": true,
+                "windowCount": window_count,
+                "latencyMs": start.elapsed().as_secs_f64() * 1000.,
+            })))
+        }
+        "get_server_info" => {
+            let (transport, port) = match TRANSPORT.with(|transport| transport.get()) {
+                Some(Transport::Http { port }) => (Some("http"), Some(port)),
+                Some(Transport::Stdio) => (Some("stdio"), None),
+                None => (None, None),
+            };
+            Ok(ToolResult::Json(serde_json::json!({
+                "name": SERVER_NAME,
+                "version": env!("CARGO_PKG_VERSION"),
+                "transport": transport,
+                "port": port,
+                "toolCount": TOOLS.len(),
+            })))
+        }
+        "get_window_properties" => {
+            let p: proto::RequestWindowProperties = deserialize_params(args)?;
+            let window_index = window_arg(state, p.window_handle)?;
+            let response = dispatch::window_properties(state, window_index)?;
+            Ok(ToolResult::Json(
+                serde_json::to_value(response).map_err(|e| format!("serialize error: {e}"))?,
+            ))
+        }
+        "find_elements_by_id" => {
+            let p: proto::RequestFindElementsById = deserialize_params(args)?;
+            let window_index = window_arg(state, p.window_handle)?;
+            let response = dispatch::find_elements_by_id(
+                state,
+                window_index,
+                &p.elements_id,
+                p.offset,
+                p.limit,
             )?;
-            let response = dispatch::find_elements_by_id(state, window_index, &p.elements_id)?;
             Ok(ToolResult::Json(
                 serde_json::to_value(response).map_err(|e| format!("serialize error: {e}"))?,
             ))
         }
+        "find_elements_by_role" => {
+            let p: proto::RequestFindElementsByRole = deserialize_params(args)?;
+            let window_index = window_arg(state, p.window_handle)?;
+            let role = proto::AccessibleRole::try_from(p.accessible_role)
+                .ok()
+                .filter(|role| *role != proto::AccessibleRole::Unknown)
+                .ok_or_else(|| {
+                    ToolError::bad_argument("accessibleRole must be a specific role, not Unknown")
+                })?;
+            let response = dispatch::find_elements_by_role(state, window_index, role)?;
+            Ok(ToolResult::Json(
+                serde_json::to_value(response).map_err(|e| format!("serialize error: {e}"))?,
+            ))
+        }
+        "wait_for_element" => {
+            let p: proto::RequestWaitForElement = deserialize_params(args)?;
+            let window_index = window_arg(state, p.window_handle)?;
+            let timeout_ms = if p.timeout_ms == 0 { 5000 } else { p.timeout_ms.min(60_000) };
+            let timeout = std::time::Duration::from_millis(timeout_ms.into());
+            let start = std::time::Instant::now();
+            loop {
+                let response =
+                    dispatch::find_elements_by_id(state, window_index, &p.elements_id, 0, None)?;
+                if !response.element_handles.is_empty() {
+                    return Ok(ToolResult::Json(
+                        serde_json::to_value(response)
+                            .map_err(|e| format!("serialize error: {e}"))?,
+                    ));
+                }
+                if start.elapsed() >= timeout {
+                    return Err(ToolError {
+                        code: ToolErrorCode::Timeout,
+                        message: format!(
+                            "no element with id '{}' appeared within {timeout_ms}ms",
+                            p.elements_id
+                        ),
+                    });
+                }
+                crate::search_api::wait_for(std::time::Duration::from_millis(100)).await;
+            }
+        }
         "get_element_properties" => {
             let p: proto::RequestElementProperties = deserialize_params(args)?;
-            let element_index = handle_to_index(
-                p.element_handle.ok_or_else(|| "missing elementHandle".to_string())?,
-            )?;
+            let element_index = element_arg(state, name, p.element_handle)?;
             let response = dispatch::element_properties(state, element_index)?;
             Ok(ToolResult::Json(
                 serde_json::to_value(response).map_err(|e| format!("serialize error: {e}"))?,
             ))
         }
+        "assert_element_property" => {
+            let p: proto::RequestAssertElementProperty = deserialize_params(args)?;
+            let element_index = element_arg(state, name, p.element_handle)?;
+            let condition = PropertyCondition::new(&p.property, &p.operator, &p.expected)
+                .map_err(ToolError::bad_argument)?;
+            let properties = dispatch::element_properties(state, element_index)?;
+            let (passed, actual) = condition.check(&properties)?;
+            let result = serde_json::json!({
+                "passed": passed,
+                "property": p.property,
+                "operator": p.operator,
+                "actual": actual.to_json(),
+                "expected": p.expected,
+            });
+            Ok(if passed { ToolResult::Json(result) } else { ToolResult::Failed(result) })
+        }
+        "poll_until" => {
+            let p: proto::RequestPollUntil = deserialize_params(args)?;
+            let element_index = element_arg(state, name, p.element_handle)?;
+            let condition = PropertyCondition::new(&p.property, &p.operator, &p.expected)
+                .map_err(ToolError::bad_argument)?;
+            let timeout_ms = if p.timeout_ms == 0 { 5000 } else { p.timeout_ms.min(60_000) };
+            let timeout = std::time::Duration::from_millis(timeout_ms.into());
+            let interval_ms = if p.interval_ms == 0 { 100 } else { p.interval_ms.min(timeout_ms) };
+            let interval = std::time::Duration::from_millis(interval_ms.into());
+            let start = std::time::Instant::now();
+            let mut polls = 0;
+            loop {
+                polls += 1;
+                let properties = dispatch::element_properties(state, element_index)?;
+                let (passed, actual) = condition.check(&properties)?;
+                if passed {
+                    return Ok(ToolResult::Json(serde_json::json!({
+                        "passed": true,
+                        "polls": polls,
+                        "elapsedMs": start.elapsed().as_millis() as u64,
+                        "actual": actual.to_json(),
+                    })));
+                }
+                if start.elapsed() >= timeout {
+                    return Err(ToolError {
+                        code: ToolErrorCode::Timeout,
+                        message: format!(
+                            "{} {} '{}' did not hold within {timeout_ms}ms ({polls} polls, last value: {})",
+                            p.property,
+                            p.operator,
+                            p.expected,
+                            actual.to_json()
+                        ),
+                    });
+                }
+                crate::search_api::wait_for(interval).await;
+            }
+        }
+        "get_element_properties_batch" => {
+            let p: proto::RequestElementPropertiesBatch = deserialize_params(args)?;
+            let results = p
+                .element_handles
+                .into_iter()
+                .map(|handle| {
+                    // Without zOrder, which would walk the whole window for every element.
+                    let properties = handle_to_index(handle)
+                        .and_then(|index| state.element(name, index))
+                        .map(|element| introspection::element_properties(&element))
+                        .and_then(|properties| {
+                            serde_json::to_value(properties)
+                                .map_err(|e| format!("serialize error: {e}"))
+                        });
+                    let handle = serde_json::to_value(handle)
+                        .map_err(|e| format!("serialize error: {e}"))?;
+                    Ok(match properties {
+                        Ok(properties) => {
+                            serde_json::json!({ "handle": handle, "properties": properties })
+                        }
+                        Err(error) => serde_json::json!({ "handle": handle, "error": error }),
+                    })
+                })
+                .collect::<Result<Vec<Value>, String>>()?;
+            Ok(ToolResult::Json(Value::Array(results)))
+        }
         "query_element_descendants" => {
             let p: proto::RequestQueryElementDescendants = deserialize_params(args)?;
-            let element_index = handle_to_index(
-                p.element_handle.ok_or_else(|| "missing elementHandle".to_string())?,
-            )?;
+            let element_index = element_arg(state, name, p.element_handle)?;
             let response = dispatch::query_element_descendants(
                 state,
                 element_index,
                 p.query_stack,
                 p.find_all,
+                p.offset,
+                p.limit,
             )?;
             Ok(ToolResult::Json(
                 serde_json::to_value(response).map_err(|e| format!("serialize error: {e}"))?,
@@ -275,89 +1313,313 @@ async fn handle_tool_call(
         }
         "get_element_tree" => {
             let p: proto::RequestGetElementTree = deserialize_params(args)?;
-            let element_handle =
-                p.element_handle.ok_or_else(|| "missing elementHandle".to_string())?;
+            let root_index = element_arg(state, name, p.element_handle)?;
             let max_elements: usize =
                 if p.max_elements == 0 { 200 } else { (p.max_elements as usize).clamp(1, 1000) };
 
-            let root_index = handle_to_index(element_handle)?;
-            let root_element = state.element("get_element_tree", root_index)?;
+            let include_invisible = p.include_invisible.unwrap_or(true);
+            let fields = element_tree_fields(&p.fields).map_err(ToolError::bad_argument)?;
 
-            let mut elements: Vec<Value> = Vec::new();
-            let mut truncated = false;
+            let root_element = state.element(name, root_index)?;
+            let project = |element: &crate::ElementHandle| {
+                let handle = element_handle_in_subtree(state, &root_element, root_index, element);
+                element_tree_node(element, handle, &fields).ok()
+            };
+            let with_parent = |mut node: Value, parent: Option<&Value>| {
+                if let Some(parent) = parent {
+                    node["parentHandle"] = parent.clone();
+                }
+                node
+            };
 
-            // Add root element
-            let root_props = introspection::element_properties(&root_element);
-            let mut root_node =
-                serde_json::to_value(root_props).map_err(|e| format!("serialize error: {e}"))?;
-            if let Some(obj) = root_node.as_object_mut() {
-                obj.insert(
-                    "handle".to_string(),
-                    serde_json::to_value(index_to_handle(root_index))
-                        .map_err(|e| format!("serialize error: {e}"))?,
+            if p.streaming {
+                let mut ndjson = String::new();
+                let (total_count, truncated) = collect_subtree(
+                    &root_element,
+                    include_invisible,
+                    max_elements,
+                    project,
+                    |node, parent| {
+                        ndjson.push_str(&with_parent(node, parent).to_string());
+                        ndjson.push('\n');
+                    },
                 );
+                let summary = serde_json::json!({
+                    "totalCount": total_count,
+                    "truncated": truncated
+                });
+                ndjson.push_str(&summary.to_string());
+                ndjson.push('\n');
+                return Ok(ToolResult::Text(ndjson));
             }
-            elements.push(root_node);
-
-            root_element.visit_descendants(|child| {
-                if elements.len() >= max_elements {
-                    truncated = true;
-                    return std::ops::ControlFlow::Break(());
-                }
-                let child_handle = state.element_to_handle(child.clone());
-                let props = introspection::element_properties(&child);
-                if let Ok(mut node) = serde_json::to_value(props) {
-                    if let (Some(obj), Ok(handle_json)) =
-                        (node.as_object_mut(), serde_json::to_value(index_to_handle(child_handle)))
-                    {
-                        obj.insert("handle".to_string(), handle_json);
-                    }
-                    elements.push(node);
-                }
-                std::ops::ControlFlow::<()>::Continue(())
-            });
-
+            let mut elements = Vec::new();
+            let (total_count, truncated) = collect_subtree(
+                &root_element,
+                include_invisible,
+                max_elements,
+                project,
+                |node, parent| elements.push(with_parent(node, parent)),
+            );
             Ok(ToolResult::Json(serde_json::json!({
                 "elements": elements,
-                "totalCount": elements.len(),
+                "totalCount": total_count,
                 "truncated": truncated
             })))
         }
+        "list_element_ids" => {
+            let p: proto::RequestListElementIds = deserialize_params(args)?;
+            let window_index = window_arg(state, p.window_handle)?;
+            let max_elements: usize =
+                if p.max_elements == 0 { 1000 } else { (p.max_elements as usize).clamp(1, 10_000) };
+            let root_index = state.root_element_handle(window_index)?;
+            let root_element = state.element(name, root_index)?;
+            let mut elements = Vec::new();
+            let (_, truncated) = collect_subtree(
+                &root_element,
+                true,
+                max_elements,
+                |element| {
+                    let id = element.id().filter(|id| !id.is_empty())?;
+                    let handle = index_to_handle(element_handle_in_subtree(
+                        state,
+                        &root_element,
+                        root_index,
+                        element,
+                    ));
+                    Some(serde_json::json!({
+                        "id": id.as_str(),
+                        "handle": serde_json::to_value(handle).ok()?,
+                    }))
+                },
+                |node, _| elements.push(node),
+            );
+            Ok(ToolResult::Json(serde_json::json!({
+                "elements": elements,
+                "totalCount": elements.len(),
+                "truncated": truncated,
+            })))
+        }
+        "get_element_at_position" => {
+            let p: proto::RequestElementAtPosition = deserialize_params(args)?;
+            let window_index = window_arg(state, p.window_handle)?;
+            let position = p.position.ok_or_else(|| ToolError::bad_argument("missing position"))?;
+            let hit = dispatch::element_at_position(state, window_index, position)?;
+            Ok(ToolResult::Json(match hit {
+                Some((handle, properties)) => serde_json::json!({
+                    "handle": serde_json::to_value(handle)
+                        .map_err(|e| format!("serialize error: {e}"))?,
+                    "properties": serde_json::to_value(properties)
+                        .map_err(|e| format!("serialize error: {e}"))?,
+                }),
+                None => Value::Null,
+            }))
+        }
+        "get_focused_element" => {
+            let p: proto::RequestFocusedElement = deserialize_params(args)?;
+            let window_index = window_arg(state, p.window_handle)?;
+            let focused = dispatch::focused_element(state, window_index)?;
+            Ok(ToolResult::Json(match focused {
+                Some((handle, properties)) => serde_json::json!({
+                    "handle": serde_json::to_value(handle)
+                        .map_err(|e| format!("serialize error: {e}"))?,
+                    "properties": serde_json::to_value(properties)
+                        .map_err(|e| format!("serialize error: {e}"))?,
+                }),
+                None => Value::Null,
+            }))
+        }
+        "get_window_text" => {
+            let p: proto::RequestWindowText = deserialize_params(args)?;
+            let window_index = window_arg(state, p.window_handle)?;
+            let entries = dispatch::window_text(state, window_index)?;
+            let text =
+                entries.iter().map(|(text, ..)| text.as_str()).collect::<Vec<_>>().join("\n");
+            let items = entries
+                .into_iter()
+                .map(|(text, handle, position)| {
+                    Ok(serde_json::json!({
+                        "text": text,
+                        "handle": serde_json::to_value(handle)?,
+                        "position": serde_json::to_value(position)?,
+                    }))
+                })
+                .collect::<Result<Vec<_>, serde_json::Error>>()
+                .map_err(|e| format!("serialize error: {e}"))?;
+            Ok(ToolResult::Json(serde_json::json!({ "text": text, "items": items })))
+        }
+        "get_clipboard" => {
+            let p: proto::RequestGetClipboard = deserialize_params(args)?;
+            let window_index = window_arg(state, p.window_handle)?;
+            let text = dispatch::clipboard_text(state, window_index, p.selection)?;
+            Ok(ToolResult::Json(serde_json::json!({ "text": text })))
+        }
+        "set_clipboard" => {
+            let p: proto::RequestSetClipboard = deserialize_params(args)?;
+            let window_index = window_arg(state, p.window_handle)?;
+            dispatch::set_clipboard_text(state, window_index, &p.text, p.selection)?;
+            let applied = dispatch::clipboard_text(state, window_index, p.selection)?.as_deref()
+                == Some(p.text.as_str());
+            Ok(ToolResult::Json(serde_json::json!({ "applied": applied })))
+        }
         "take_screenshot" => {
             let p: proto::RequestTakeSnapshot = deserialize_params(args)?;
-            let window_index = handle_to_index(
-                p.window_handle.ok_or_else(|| "missing windowHandle".to_string())?,
-            )?;
-            let response = dispatch::take_snapshot(state, window_index, "image/png")?;
-            let png_data = response.window_contents_as_encoded_image;
+            let window_index = window_arg(state, p.window_handle)?;
+            let crop_to = match p.element_handle {
+                Some(handle) => Some(element_arg(state, name, Some(handle))?),
+                None => None,
+            };
+            let mime_type =
+                screenshot_mime_type(&p.image_mime_type).map_err(ToolError::bad_argument)?;
+            let quality = introspection::jpeg_quality(p.quality);
+            let response =
+                dispatch::take_snapshot(state, window_index, mime_type, crop_to, quality)?;
+            let data = response.window_contents_as_encoded_image;
             Ok(ToolResult::Image {
-                meta: serde_json::json!({ "sizeBytes": png_data.len() }),
-                png_data,
+                meta: serde_json::json!({
+                    "sizeBytes": data.len(),
+                    "width": response.width,
+                    "height": response.height,
+                }),
+                data,
+                mime_type,
             })
         }
+        "save_screenshot" => {
+            let p: proto::RequestSaveScreenshot = deserialize_params(args)?;
+            let window_index = window_arg(state, p.window_handle)?;
+            let crop_to = match p.element_handle {
+                Some(handle) => Some(element_arg(state, name, Some(handle))?),
+                None => None,
+            };
+            let mime_type =
+                screenshot_mime_type(&p.image_mime_type).map_err(ToolError::bad_argument)?;
+            let file_name =
+                screenshot_file_name(&p.file_name, mime_type).map_err(ToolError::bad_argument)?;
+            let quality = introspection::jpeg_quality(p.quality);
+            let response =
+                dispatch::take_snapshot(state, window_index, mime_type, crop_to, quality)?;
+            let dir = SCREENSHOT_DIR.with(|dir| dir.borrow().clone());
+            std::fs::create_dir_all(&dir).map_err(|e| {
+                format!("cannot create screenshot directory {}: {e}", dir.display())
+            })?;
+            let path = dir.join(file_name);
+            let data = response.window_contents_as_encoded_image;
+            std::fs::write(&path, &data)
+                .map_err(|e| format!("cannot write {}: {e}", path.display()))?;
+            Ok(ToolResult::Json(serde_json::json!({
+                "path": path.display().to_string(),
+                "width": response.width,
+                "height": response.height,
+                "sizeBytes": data.len(),
+                "mimeType": mime_type,
+            })))
+        }
+        "compare_screenshots" => {
+            let p: proto::RequestCompareScreenshots = deserialize_params(args)?;
+            let baseline = if !p.baseline.is_empty() {
+                decode_image(&p.baseline, "baseline")?
+            } else if !p.baseline_path.is_empty() {
+                let path =
+                    screenshot_baseline_path(&p.baseline_path).map_err(ToolError::bad_argument)?;
+                let data = std::fs::read(&path).map_err(|e| {
+                    ToolError::bad_argument(format!("cannot read {}: {e}", path.display()))
+                })?;
+                decode_image(&data, "baselinePath")?
+            } else {
+                return Err(ToolError::bad_argument("either baseline or baselinePath is required"));
+            };
+            let candidate = if !p.candidate.is_empty() {
+                decode_image(&p.candidate, "candidate")?
+            } else {
+                let window_index = window_arg(state, p.window_handle)?;
+                dispatch::capture_window(state, window_index)?
+            };
+            let tolerance = p.tolerance.min(u8::MAX.into()) as u8;
+            let Some(comparison) = introspection::compare_images(&baseline, &candidate, tolerance)
+            else {
+                return Ok(ToolResult::Json(serde_json::json!({
+                    "sizeMatches": false,
+                    "baseline": { "width": baseline.width(), "height": baseline.height() },
+                    "candidate": { "width": candidate.width(), "height": candidate.height() },
+                })));
+            };
+            let (width, height) = baseline.dimensions();
+            let total_pixels = u64::from(width) * u64::from(height);
+            let similarity = if total_pixels == 0 {
+                1.
+            } else {
+                1. - comparison.differing_pixels as f64 / total_pixels as f64
+            };
+            let result = serde_json::json!({
+                "sizeMatches": true,
+                "width": width,
+                "height": height,
+                "differingPixels": comparison.differing_pixels,
+                "totalPixels": total_pixels,
+                "similarity": similarity,
+            });
+            if !p.include_diff_image {
+                return Ok(ToolResult::Json(result));
+            }
+            let data =
+                introspection::encode_image(comparison.diff_image, image::ImageFormat::Png, None)
+                    .map_err(|e| format!("error encoding diff image: {e}"))?;
+            Ok(ToolResult::Image { data, mime_type: "image/png", meta: result })
+        }
         "click_element" => {
             let p: proto::RequestElementClick = deserialize_params(args)?;
-            let element_index = handle_to_index(
-                p.element_handle.ok_or_else(|| "missing elementHandle".to_string())?,
-            )?;
-            let button = proto::PointerEventButton::try_from(p.button)
-                .map_err(|_| format!("invalid button value: {}", p.button))?;
-            let action = proto::ClickAction::try_from(p.action)
-                .map_err(|_| format!("invalid action value: {}", p.action))?;
+            let element_index = element_arg(state, name, p.element_handle)?;
+            let button = proto::PointerEventButton::from_number(p.button)
+                .map_err(ToolError::bad_argument)?;
+            let action =
+                proto::ClickAction::from_number(p.action).map_err(ToolError::bad_argument)?;
             dispatch::click(state, element_index, action, button).await?;
             let response = proto::ElementClickResponse {};
             Ok(ToolResult::Json(
                 serde_json::to_value(response).map_err(|e| format!("serialize error: {e}"))?,
             ))
         }
+        "drag_element_to" => {
+            let p: proto::RequestElementDragTo = deserialize_params(args)?;
+            let element_index = element_arg(state, name, p.element_handle)?;
+            let target = match (p.target_element_handle, p.target_position) {
+                (Some(handle), None) => {
+                    dispatch::element_center(state, element_arg(state, name, Some(handle))?)?
+                }
+                (None, Some(position)) => position,
+                _ => {
+                    return Err(ToolError::bad_argument(
+                        "pass exactly one of targetElementHandle and targetPosition",
+                    ));
+                }
+            };
+            let button = proto::PointerEventButton::from_number(p.button)
+                .map_err(ToolError::bad_argument)?;
+            let steps = if p.steps == 0 { 10 } else { p.steps.min(1000) as usize };
+            let start = dispatch::element_center(state, element_index)?;
+            dispatch::drag_to(state, element_index, target, button, steps).await?;
+            Ok(ToolResult::Json(serde_json::json!({
+                "from": serde_json::to_value(start).map_err(|e| format!("serialize error: {e}"))?,
+                "to": serde_json::to_value(target).map_err(|e| format!("serialize error: {e}"))?,
+                "steps": steps,
+            })))
+        }
+        "hover_element" => {
+            let p: proto::RequestElementHover = deserialize_params(args)?;
+            let element_index = element_arg(state, name, p.element_handle)?;
+            let position = dispatch::hover(state, element_index)?;
+            Ok(ToolResult::Json(serde_json::json!({
+                "position": serde_json::to_value(position)
+                    .map_err(|e| format!("serialize error: {e}"))?,
+            })))
+        }
         "drag_element" => {
             let p: proto::RequestElementDrag = deserialize_params(args)?;
-            let element_index = handle_to_index(
-                p.element_handle.ok_or_else(|| "missing elementHandle".to_string())?,
-            )?;
-            let target = p.target.ok_or_else(|| "missing target position".to_string())?;
-            let button = proto::PointerEventButton::try_from(p.button)
-                .map_err(|_| format!("invalid button value: {}", p.button))?;
+            let element_index = element_arg(state, name, p.element_handle)?;
+            let target =
+                p.target.ok_or_else(|| ToolError::bad_argument("missing target position"))?;
+            let button = proto::PointerEventButton::from_number(p.button)
+                .map_err(ToolError::bad_argument)?;
             dispatch::drag(state, element_index, target, button).await?;
             let response = proto::ElementDragResponse {};
             Ok(ToolResult::Json(
@@ -366,11 +1628,9 @@ async fn handle_tool_call(
         }
         "invoke_accessibility_action" => {
             let p: proto::RequestInvokeElementAccessibilityAction = deserialize_params(args)?;
-            let element_index = handle_to_index(
-                p.element_handle.ok_or_else(|| "missing elementHandle".to_string())?,
-            )?;
-            let action = proto::ElementAccessibilityAction::try_from(p.action)
-                .map_err(|_| format!("invalid action value: {}", p.action))?;
+            let element_index = element_arg(state, name, p.element_handle)?;
+            let action = proto::ElementAccessibilityAction::from_number(p.action)
+                .map_err(ToolError::bad_argument)?;
             dispatch::invoke_accessibility_action(state, element_index, action)?;
             let response = proto::InvokeElementAccessibilityActionResponse {};
             Ok(ToolResult::Json(
@@ -379,9 +1639,7 @@ async fn handle_tool_call(
         }
         "set_element_value" => {
             let p: proto::RequestSetElementAccessibleValue = deserialize_params(args)?;
-            let element_index = handle_to_index(
-                p.element_handle.ok_or_else(|| "missing elementHandle".to_string())?,
-            )?;
+            let element_index = element_arg(state, name, p.element_handle)?;
             dispatch::set_accessible_value(state, element_index, p.value)?;
             let response = proto::SetElementAccessibleValueResponse {};
             Ok(ToolResult::Json(
@@ -390,11 +1648,9 @@ async fn handle_tool_call(
         }
         "dispatch_key_event" => {
             let p: proto::RequestDispatchKeyEvent = deserialize_params(args)?;
-            let window_index = handle_to_index(
-                p.window_handle.ok_or_else(|| "missing windowHandle".to_string())?,
-            )?;
-            let event_type = proto::KeyEventType::try_from(p.event_type)
-                .map_err(|_| format!("invalid eventType value: {}", p.event_type))?;
+            let window_index = window_arg(state, p.window_handle)?;
+            let event_type =
+                proto::KeyEventType::from_number(p.event_type).map_err(ToolError::bad_argument)?;
             let events: Vec<i_slint_core::platform::WindowEvent> = match event_type {
                 proto::KeyEventType::Press => {
                     vec![i_slint_core::platform::WindowEvent::KeyPressed { text: p.text.into() }]
@@ -415,6 +1671,20 @@ async fn handle_tool_call(
                 serde_json::to_value(response).map_err(|e| format!("serialize error: {e}"))?,
             ))
         }
+        "dispatch_key_sequence" => {
+            let p: proto::RequestDispatchKeySequence = deserialize_params(args)?;
+            let window_index = window_arg(state, p.window_handle)?;
+            for event in key_sequence_events(&p.steps).map_err(ToolError::bad_argument)? {
+                state.dispatch_window_event(window_index, event)?;
+            }
+            Ok(ToolResult::Json(serde_json::json!({ "stepCount": p.steps.len() })))
+        }
+        "close_window" => {
+            let p: proto::RequestCloseWindow = deserialize_params(args)?;
+            let window_index = window_arg(state, p.window_handle)?;
+            let closed = dispatch::close_window(state, window_index)?;
+            Ok(ToolResult::Json(serde_json::json!({ "closed": closed })))
+        }
         "start_event_recording" => {
             let response = dispatch::start_event_recording(state);
             Ok(ToolResult::Json(
@@ -427,7 +1697,7 @@ async fn handle_tool_call(
                 serde_json::to_value(response).map_err(|e| format!("serialize error: {e}"))?,
             ))
         }
-        _ => Err(format!("Unknown tool: {name}")),
+        _ => Err(ToolError::bad_argument(format!("Unknown tool: {name}"))),
     }
 }
 
@@ -451,35 +1721,268 @@ fn json_rpc_error(id: &Value, code: i32, message: String) -> Value {
     })
 }
 
+// ============================================================================
+// Resources
+// ============================================================================
+
+const WINDOW_RESOURCE_PREFIX: &str = "slint://window/";
+
+fn window_resource_uri(handle: proto::Handle) -> String {
+    format!("{WINDOW_RESOURCE_PREFIX}{}/{}", handle.index, handle.generation)
+}
+
+/// Parses a `slint://window/<index>/<generation>` URI into a window arena index.
+fn parse_window_resource_uri(uri: &str) -> Result<introspection::ArenaIndex, String> {
+    let invalid = || format!("unknown resource '{uri}'");
+    let (index, generation) = uri
+        .strip_prefix(WINDOW_RESOURCE_PREFIX)
+        .and_then(|rest| rest.split_once('/'))
+        .ok_or_else(invalid)?;
+    let index = index.parse().map_err(|_| invalid())?;
+    let generation = generation.parse().map_err(|_| invalid())?;
+    handle_to_index(proto::Handle { index, generation }).map_err(|_| invalid())
+}
+
+/// Lists each window as a resource whose content is its get_window_properties JSON.
+fn list_resources(state: &IntrospectionState) -> Value {
+    let resources: Vec<Value> = dispatch::list_windows(state)
+        .window_handles
+        .into_iter()
+        .map(|handle| {
+            serde_json::json!({
+                "uri": window_resource_uri(handle),
+                "name": format!("window-{}", handle.index),
+                "title": format!("Window {}", handle.index),
+                "description": "Window properties: size, position, scale factor, state, and rootElementHandle.",
+                "mimeType": "application/json",
+            })
+        })
+        .collect();
+    serde_json::json!({ "resources": resources })
+}
+
+fn read_resource(state: &IntrospectionState, uri: &str) -> Result<Value, String> {
+    let window_index = parse_window_resource_uri(uri)?;
+    let properties = dispatch::window_properties(state, window_index)
+        .map_err(|_| format!("unknown resource '{uri}'"))?;
+    let text =
+        serde_json::to_string_pretty(&properties).map_err(|e| format!("serialize error: {e}"))?;
+    Ok(serde_json::json!({
+        "contents": [{ "uri": uri, "mimeType": "application/json", "text": text }]
+    }))
+}
+
+// ============================================================================
+// Prompts
+// ============================================================================
+
+struct PromptArgument {
+    name: &'static str,
+    description: &'static str,
+    required: bool,
+}
+
+struct PromptDef {
+    name: &'static str,
+    title: &'static str,
+    description: &'static str,
+    arguments: &'static [PromptArgument],
+    /// The prompt text. `{name}` is replaced by the value of the argument called `name`,
+    /// or by an empty string if an optional argument is not given.
+    template: &'static str,
+}
+
+const PROMPTS: &[PromptDef] = &[
+    PromptDef {
+        name: "inspect_window",
+        title: "Inspect a window",
+        description: "Walk through the UI of a window: its properties, element tree, and a screenshot.",
+        arguments: &[PromptArgument {
+            name: "focus",
+            description: "Optional part of the UI to pay particular attention to.",
+            required: false,
+        }],
+        template: concat!(
+            "Inspect the user interface of the running Slint application.\n\n",
+            "1. Call list_windows and pick the window to inspect (ask if there are several and it's unclear which one is meant).\n",
+            "2. Call get_window_properties for its size, scale factor and rootElementHandle.\n",
+            "3. Call get_element_tree with maxElements=50, and again with a higher limit if the tree is truncated.\n",
+            "4. Call take_screenshot to see what is rendered.\n",
+            "5. Summarize the structure of the UI: the main areas, the interactive elements and their state.\n\n",
+            "Pay particular attention to: {focus}",
+        ),
+    },
+    PromptDef {
+        name: "debug_ui",
+        title: "Debug a UI problem",
+        description: "Reproduce and analyze a problem in the UI, using the introspection and interaction tools.",
+        arguments: &[PromptArgument {
+            name: "problem",
+            description: "Description of the problem, e.g. \"the Save button does nothing\".",
+            required: true,
+        }],
+        template: concat!(
+            "Debug the following problem in the running Slint application: {problem}\n\n",
+            "1. Call list_windows, get_window_properties and get_element_tree to find the elements involved.\n",
+            "2. Call get_element_properties on them and check their state (enabled, visible, size, accessible value).\n",
+            "3. Call take_screenshot to see the current state.\n",
+            "4. Call start_event_recording, reproduce the problem with click_element, set_element_value or dispatch_key_event, then call stop_event_recording.\n",
+            "5. Take another screenshot and compare the element properties with the state before.\n",
+            "6. Explain what you observed, whether the input reached the application, and what the likely cause is.",
+        ),
+    },
+];
+
+fn list_prompts() -> Value {
+    let prompts: Vec<Value> = PROMPTS
+        .iter()
+        .map(|prompt| {
+            let arguments: Vec<Value> = prompt
+                .arguments
+                .iter()
+                .map(|argument| {
+                    serde_json::json!({
+                        "name": argument.name,
+                        "description": argument.description,
+                        "required": argument.required,
+                    })
+                })
+                .collect();
+            serde_json::json!({
+                "name": prompt.name,
+                "title": prompt.title,
+                "description": prompt.description,
+                "arguments": arguments,
+            })
+        })
+        .collect();
+    serde_json::json!({ "prompts": prompts })
+}
+
+fn get_prompt(name: &str, arguments: &Value) -> Result<Value, String> {
+    let prompt = PROMPTS
+        .iter()
+        .find(|prompt| prompt.name == name)
+        .ok_or_else(|| format!("unknown prompt '{name}'"))?;
+    let mut text = prompt.template.to_string();
+    for argument in prompt.arguments {
+        let value = arguments.get(argument.name).and_then(|v| v.as_str()).unwrap_or_default();
+        if argument.required && value.is_empty() {
+            return Err(format!("missing required argument '{}'", argument.name));
+        }
+        text = text.replace(&format!("{{{}}}", argument.name), value);
+    }
+    Ok(serde_json::json!({
+        "description": prompt.description,
+        "messages": [{ "role": "user", "content": { "type": "text", "text": text.trim_end() } }],
+    }))
+}
+
+// ============================================================================
+// Request log
+// ============================================================================
+
+/// Appends one line of JSON per handled message to the `SLINT_MCP_LOG_FILE`, if set:
+/// the time in milliseconds since the Unix epoch, the request (or its raw text if it's not
+/// valid JSON), the proto request type of a tool call, and the response.
+fn log_exchange(body: &str, response: Option<&Value>) {
+    REQUEST_LOG.with(|log| {
+        let mut log = log.borrow_mut();
+        let Some(file) = log.as_mut() else {
+            return;
+        };
+        let request = serde_json::from_str::<Value>(body).unwrap_or_else(|_| body.into());
+        let request_type = (request["method"] == "tools/call")
+            .then(|| request["params"]["name"].as_str())
+            .flatten()
+            .and_then(|name| TOOLS.iter().find(|tool| tool.name == name))
+            .map(|tool| tool.request_type);
+        let timestamp = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |duration| duration.as_millis());
+        let entry = serde_json::json!({
+            "timestampMs": timestamp,
+            "request": request,
+            "requestType": request_type,
+            "response": response,
+        });
+        use std::io::Write;
+        if let Err(e) = writeln!(file, "{entry}") {
+            // Logging is a debugging aid, so give up on it rather than on the request.
+            eprintln!("SLINT_MCP_LOG_FILE: cannot write request log, disabling it: {e}");
+            *log = None;
+        }
+    });
+}
+
+/// The name the server reports in `initialize` and `get_server_info`.
+const SERVER_NAME: &str = "slint-mcp-embedded";
+/// The MCP protocol version the server implements.
+const PROTOCOL_VERSION: &str = "2025-06-18";
+
 async fn handle_mcp_request(state: &IntrospectionState, body: &str) -> Option<Value> {
+    let response = process_mcp_request(state, body).await;
+    log_exchange(body, response.as_ref());
+    response
+}
+
+async fn process_mcp_request(state: &IntrospectionState, body: &str) -> Option<Value> {
     let request: Value = match serde_json::from_str(body) {
         Ok(v) => v,
         Err(e) => return Some(json_rpc_error(&Value::Null, -32700, format!("Parse error: {e}"))),
     };
 
+    // The MCP protocol version we advertise dropped support for JSON-RPC batches.
     if request.is_array() {
         return Some(json_rpc_error(
             &Value::Null,
             -32600,
-            "Batch requests are not supported".into(),
+            format!(
+                "Invalid Request: batches are not supported in protocol version {PROTOCOL_VERSION}"
+            ),
         ));
     }
+    handle_message(state, request).await
+}
 
+/// Handles a single JSON-RPC message, returning `None` for notifications.
+async fn handle_message(state: &IntrospectionState, request: Value) -> Option<Value> {
     let id = request.get("id").cloned().unwrap_or(Value::Null);
     let method = request.get("method").and_then(|v| v.as_str()).unwrap_or("");
     let is_notification = request.get("id").is_none();
 
+    if request.get("jsonrpc").and_then(|v| v.as_str()) != Some("2.0") {
+        return Some(json_rpc_error(
+            &id,
+            -32600,
+            "Invalid Request: the jsonrpc field must be \"2.0\"".into(),
+        ));
+    }
+
+    // Only notifications (all of which live under `notifications/` in MCP) may omit the id.
+    // Reject anything else up front, so that e.g. a tools/call without an id doesn't run
+    // its side effects without the client ever getting an acknowledgement.
+    if is_notification && !method.starts_with("notifications/") {
+        return Some(json_rpc_error(
+            &Value::Null,
+            -32600,
+            format!("Invalid Request: '{method}' is not a notification and requires an id"),
+        ));
+    }
+
     let response = match method {
         "initialize" => json_rpc_success(
             &id,
             serde_json::json!({
-                "protocolVersion": "2025-06-18",
+                "protocolVersion": PROTOCOL_VERSION,
                 "capabilities": {
-                    "tools": {}
+                    "tools": {},
+                    "resources": {},
+                    "prompts": {}
                 },
                 "serverInfo": {
-                    "name": "slint-mcp-embedded",
-                    "version": "0.1.0"
+                    "name": SERVER_NAME,
+                    "version": env!("CARGO_PKG_VERSION")
                 },
                 "instructions": concat!(
                     "This is an embedded MCP server in a running Slint application. ",
@@ -489,10 +1992,11 @@ async fn handle_mcp_request(state: &IntrospectionState, body: &str) -> Option<Va
                     "1. list_windows → get window handles\n",
                     "2. get_window_properties → get size, position, and the rootElementHandle\n",
                     "3. get_element_tree (start with maxElements=50) → flat list of the UI hierarchy with types, IDs, accessibility info, and handles\n",
-                    "4. Drill down: use query_element_descendants to search by type, ID, or accessible role; or find_elements_by_id for known IDs\n",
+                    "   (or list_element_ids for just the IDs and handles of all named elements)\n",
+                    "4. Drill down: use query_element_descendants to search by type, ID, or accessible role; find_elements_by_id for known IDs (wait_for_element if the element may not exist yet); or find_elements_by_role for all elements of a role\n",
                     "5. get_element_properties → full details on a specific element\n",
                     "6. take_screenshot → visual snapshot (returned as inline image)\n",
-                    "7. Interact: click_element, drag_element, set_element_value, invoke_accessibility_action, dispatch_key_event\n",
+                    "7. Interact: click_element, drag_element, set_element_value, invoke_accessibility_action, dispatch_key_event, dispatch_key_sequence\n",
                     "8. start_event_recording → then interact → stop_event_recording to verify the runtime received and processed expected input/window events\n",
                     "9. take_screenshot again to verify the visual effect\n\n",
 
@@ -510,7 +2014,7 @@ async fn handle_mcp_request(state: &IntrospectionState, body: &str) -> Option<Va
                     "Do not reuse a window handle as an element handle or vice versa — they are not interchangeable.\n\n",
 
                     "# Enum values\n\n",
-                    "Enum fields accept PascalCase strings:\n",
+                    "Enum fields accept PascalCase strings; the button, action and eventType arguments also accept any case, with or without underscores (e.g. \"left\", \"double_click\"):\n",
                     "- AccessibleRole: Unknown, Button, Checkbox, Combobox, List, Slider, Spinbox, Tab, TabList, Text, Table, Tree, ProgressIndicator, TextInput, Switch, ListItem, TabPanel, Groupbox, Image, RadioButton, RadioGroup, Banner, Complementary, ContentInfo, Form, Main, Navigation, Region, Search\n",
                     "- PointerEventButton: Left, Right, Middle, Back, Forward, Other\n",
                     "- ClickAction: SingleClick, DoubleClick\n",
@@ -527,24 +2031,57 @@ async fn handle_mcp_request(state: &IntrospectionState, body: &str) -> Option<Va
                     "- {\"matchElementTypeName\": \"Button\"} — match by exact Slint type name\n",
                     "- {\"matchElementTypeNameOrBase\": \"TouchArea\"} — match by type or inherited base\n",
                     "- {\"matchElementAccessibleRole\": \"Button\"} — match by accessible role (PascalCase)\n",
+                    "- {\"matchAnyOf\": {\"alternatives\": [...]}} — match if any alternative matches; each alternative is one matchElementTypeName, matchElementTypeNameOrBase or matchElementAccessibleRole object\n",
                     "Instructions are applied in order to build a query pipeline.\n\n",
 
                     "# Tips\n\n",
                     "- Start with get_element_tree to understand the UI structure before making targeted queries.\n",
                     "- Element IDs are qualified: 'ComponentName::element-id'. Use get_element_tree to discover them.\n",
                     "- After clicking or setting values, take a screenshot to verify the visual result.\n",
+                    "- To check the outcome of an interaction without reading through all properties, use assert_element_property (e.g. accessible_checked eq \"true\").\n",
+                    "- To identify something you see in a screenshot, use get_element_at_position with its logical coordinates (pixel coordinates divided by the window's scale factor).\n",
+                    "- If an interaction shows new elements asynchronously (animations, data loads), use wait_for_element instead of retrying find_elements_by_id.\n",
+                    "- To wait for an existing element to change (e.g. a status label after a click), use poll_until instead of polling get_element_properties yourself.\n",
                     "- For text input: find the TextInput element, then use set_element_value to set its content.\n",
+                    "- To test copy and paste, use set_clipboard and get_clipboard together with dispatch_key_event. They access the system clipboard on desktop platforms.\n",
                     "- For buttons: use click_element, or invoke_accessibility_action with 'Default_' for the default action.\n",
                     "- For sliders: use invoke_accessibility_action with 'Increment'/'Decrement', set_element_value with the numeric value as a string, or drag_element to drag the thumb to a position.\n",
+                    "- To see hover effects (tooltips, highlights), use hover_element before taking a screenshot.\n",
                     "- For drag gestures (scrollable areas, drag handles, custom sliders): use drag_element with the target position in logical coordinates.\n",
-                    "- For checkboxes/switches: use click_element or invoke_accessibility_action with 'Default_'.\n"
+                    "- For drag-and-drop onto another element: use drag_element_to with targetElementHandle.\n",
+                    "- For checkboxes/switches: use click_element or invoke_accessibility_action with 'Default_'.\n",
+                    "- To follow keyboard navigation: dispatch_key_event with Tab, then get_focused_element.\n"
                 )
             }),
         ),
         "notifications/initialized" => {
             return None;
         }
+        "ping" => json_rpc_success(&id, serde_json::json!({})),
         "tools/list" => json_rpc_success(&id, tool_definitions()),
+        "resources/list" => json_rpc_success(&id, list_resources(state)),
+        "resources/read" => {
+            match request.get("params").and_then(|p| p.get("uri")).and_then(|v| v.as_str()) {
+                // -32002 is the MCP error code for "resource not found".
+                Some(uri) => match read_resource(state, uri) {
+                    Ok(result) => json_rpc_success(&id, result),
+                    Err(e) => json_rpc_error(&id, -32002, e),
+                },
+                None => json_rpc_error(&id, -32602, "missing uri parameter".into()),
+            }
+        }
+        "prompts/list" => json_rpc_success(&id, list_prompts()),
+        "prompts/get" => {
+            let params = request.get("params").cloned().unwrap_or(serde_json::json!({}));
+            let arguments = params.get("arguments").cloned().unwrap_or(serde_json::json!({}));
+            match params.get("name").and_then(|v| v.as_str()) {
+                Some(name) => match get_prompt(name, &arguments) {
+                    Ok(result) => json_rpc_success(&id, result),
+                    Err(e) => json_rpc_error(&id, -32602, e),
+                },
+                None => json_rpc_error(&id, -32602, "missing name parameter".into()),
+            }
+        }
         "tools/call" => {
             let params = request.get("params").cloned().unwrap_or(serde_json::json!({}));
             let tool_name = params.get("name").and_then(|v| v.as_str()).unwrap_or("");
@@ -552,13 +2089,14 @@ async fn handle_mcp_request(state: &IntrospectionState, body: &str) -> Option<Va
 
             match handle_tool_call(state, tool_name, &tool_args).await {
                 Ok(result) => {
+                    let is_error = matches!(result, ToolResult::Failed(_));
                     let content = match result {
-                        ToolResult::Image { png_data, meta } => {
-                            let b64 = base64::engine::general_purpose::STANDARD.encode(&png_data);
+                        ToolResult::Image { data, mime_type, meta } => {
+                            let b64 = base64::engine::general_purpose::STANDARD.encode(&data);
                             let mut blocks = vec![serde_json::json!({
                                 "type": "image",
                                 "data": b64,
-                                "mimeType": "image/png"
+                                "mimeType": mime_type
                             })];
                             if !meta.as_object().is_none_or(|o| o.is_empty()) {
                                 blocks.push(serde_json::json!({
@@ -568,19 +2106,29 @@ async fn handle_mcp_request(state: &IntrospectionState, body: &str) -> Option<Va
                             }
                             blocks
                         }
-                        ToolResult::Json(value) => {
+                        ToolResult::Json(value) | ToolResult::Failed(value) => {
                             vec![serde_json::json!({
                                 "type": "text",
                                 "text": serde_json::to_string_pretty(&value).unwrap()
                             })]
                         }
+                        ToolResult::Text(text) => {
+                            vec![serde_json::json!({ "type": "text", "text": text })]
+                        }
                     };
-                    json_rpc_success(&id, serde_json::json!({ "content": content }))
+                    let mut result = serde_json::json!({ "content": content });
+                    if is_error {
+                        result["isError"] = true.into();
+                    }
+                    json_rpc_success(&id, result)
                 }
                 Err(e) => json_rpc_success(
                     &id,
                     serde_json::json!({
-                        "content": [{ "type": "text", "text": format!("Error: {e}") }],
+                        "content": [{ "type": "text", "text": format!("Error: {}", e.message) }],
+                        "structuredContent": {
+                            "error": { "code": e.code.as_str(), "message": e.message }
+                        },
                         "isError": true
                     }),
                 ),
@@ -597,13 +2145,197 @@ async fn handle_mcp_request(state: &IntrospectionState, body: &str) -> Option<Va
     if is_notification { None } else { Some(response) }
 }
 
+// ============================================================================
+// Configuration
+// ============================================================================
+
+/// The transport MCP messages are exchanged over, selected via `SLINT_MCP_TRANSPORT`.
+#[derive(Clone, Copy)]
+enum Transport {
+    /// Streamable HTTP on `127.0.0.1:<port>`. This is the default and requires `SLINT_MCP_PORT`.
+    Http { port: u16 },
+    /// Newline-delimited JSON-RPC on the process's stdin and stdout, for MCP clients that
+    /// launch the application themselves.
+    Stdio,
+}
+
+/// Runtime configuration of the server, read from `SLINT_MCP_*` environment variables.
+struct ServerConfig {
+    transport: Transport,
+    /// Maximum time to wait for a complete HTTP request, from its first byte on, before
+    /// dropping the connection.
+    request_timeout: std::time::Duration,
+    /// Maximum time a connection may wait for the first byte of its next request.
+    idle_timeout: std::time::Duration,
+    /// Maximum size of an HTTP request body, in bytes.
+    max_body_size: usize,
+    /// Directory save_screenshot writes to and compare_screenshots reads baselines from.
+    screenshot_dir: std::path::PathBuf,
+    /// File that requests and responses are appended to, for debugging.
+    log_file: Option<std::path::PathBuf>,
+}
+
+const DEFAULT_REQUEST_TIMEOUT_MS: u64 = 30_000;
+const DEFAULT_IDLE_TIMEOUT_MS: u64 = 300_000;
+const DEFAULT_MAX_BODY_MB: usize = 4;
+
+impl ServerConfig {
+    fn new(transport: Transport) -> Self {
+        Self {
+            transport,
+            request_timeout: std::time::Duration::from_millis(DEFAULT_REQUEST_TIMEOUT_MS),
+            idle_timeout: std::time::Duration::from_millis(DEFAULT_IDLE_TIMEOUT_MS),
+            max_body_size: DEFAULT_MAX_BODY_MB * 1024 * 1024,
+            screenshot_dir: default_screenshot_dir(),
+            log_file: None,
+        }
+    }
+
+    /// Returns `None` if the environment doesn't ask for a server to be started.
+    fn from_env() -> Option<Self> {
+        let transport = match std::env::var("SLINT_MCP_TRANSPORT").as_deref() {
+            Ok("stdio") => Transport::Stdio,
+            Ok("http") | Err(_) => {
+                let port_str = std::env::var("SLINT_MCP_PORT").ok()?;
+                match port_str.parse() {
+                    Ok(port) => Transport::Http { port },
+                    Err(_) => {
+                        eprintln!("SLINT_MCP_PORT: invalid port number '{port_str}'");
+                        return None;
+                    }
+                }
+            }
+            Ok(other) => {
+                eprintln!(
+                    "SLINT_MCP_TRANSPORT: unknown transport '{other}' (expected 'http' or 'stdio')"
+                );
+                return None;
+            }
+        };
+
+        let mut config = Self::new(transport);
+        if let Ok(timeout_str) = std::env::var("SLINT_MCP_REQUEST_TIMEOUT_MS") {
+            match timeout_str.parse::<u64>() {
+                Ok(ms) if ms > 0 => config.request_timeout = std::time::Duration::from_millis(ms),
+                _ => eprintln!(
+                    "SLINT_MCP_REQUEST_TIMEOUT_MS: invalid timeout '{timeout_str}', using {DEFAULT_REQUEST_TIMEOUT_MS}ms"
+                ),
+            }
+        }
+        if let Ok(timeout_str) = std::env::var("SLINT_MCP_IDLE_TIMEOUT_MS") {
+            match timeout_str.parse::<u64>() {
+                Ok(ms) if ms > 0 => config.idle_timeout = std::time::Duration::from_millis(ms),
+                _ => eprintln!(
+                    "SLINT_MCP_IDLE_TIMEOUT_MS: invalid timeout '{timeout_str}', using {DEFAULT_IDLE_TIMEOUT_MS}ms"
+                ),
+            }
+        }
+        if let Ok(size_str) = std::env::var("SLINT_MCP_MAX_BODY_MB") {
+            match size_str.parse::<usize>() {
+                Ok(mb) if mb > 0 => config.max_body_size = mb.saturating_mul(1024 * 1024),
+                _ => eprintln!(
+                    "SLINT_MCP_MAX_BODY_MB: invalid size '{size_str}', using {DEFAULT_MAX_BODY_MB} MB"
+                ),
+            }
+        }
+        if let Some(dir) =
+            std::env::var_os("SLINT_MCP_SCREENSHOT_DIR").filter(|dir| !dir.is_empty())
+        {
+            config.screenshot_dir = dir.into();
+        }
+        config.log_file =
+            std::env::var_os("SLINT_MCP_LOG_FILE").filter(|path| !path.is_empty()).map(Into::into);
+        Some(config)
+    }
+}
+
+fn default_screenshot_dir() -> std::path::PathBuf {
+    std::env::temp_dir().join("slint-mcp-screenshots")
+}
+
+async fn run_server(state: Rc<IntrospectionState>, config: Rc<ServerConfig>) {
+    match config.transport {
+        Transport::Http { port } => run_http_server(state, config.clone(), port).await,
+        Transport::Stdio => run_stdio_server(state).await,
+    }
+}
+
+// ============================================================================
+// Stdio Server
+// ============================================================================
+
+/// Handle one line read from stdin, returning the line to write back to stdout, if any.
+async fn handle_stdio_message(state: &IntrospectionState, line: &str) -> Option<String> {
+    if line.trim().is_empty() {
+        return None;
+    }
+    // Compact serialization never contains a newline, as required by the stdio transport.
+    handle_mcp_request(state, line).await.map(|response| serde_json::to_string(&response).unwrap())
+}
+
+async fn run_stdio_server(state: Rc<IntrospectionState>) {
+    use futures_lite::AsyncBufReadExt;
+    use futures_lite::StreamExt;
+
+    let mut lines =
+        futures_lite::io::BufReader::new(blocking::Unblock::new(std::io::stdin())).lines();
+    let mut stdout = blocking::Unblock::new(std::io::stdout());
+    eprintln!("Slint MCP server listening on stdio");
+
+    while let Some(line) = lines.next().await {
+        let Ok(line) = line else { break };
+        let Some(response) = handle_stdio_message(&state, &line).await else { continue };
+        let written = async {
+            stdout.write_all(response.as_bytes()).await?;
+            stdout.write_all(b"\n").await?;
+            stdout.flush().await
+        };
+        if let Err(e) = written.await {
+            eprintln!("MCP server: stdout write error: {e}");
+            break;
+        }
+    }
+}
+
 // ============================================================================
 // HTTP Server
 // ============================================================================
 
+/// Like [`read_http_request`], but gives up if the client has not sent a complete
+/// request within `timeout` of its first byte, so a stalled client can't hold the connection
+/// forever. The wait for that first byte is limited by `idle_timeout` instead, as keep-alive
+/// connections may stay quiet for a while between requests.
+async fn read_http_request_with_timeout(
+    stream: &mut async_net::TcpStream,
+    mut carry: Vec<u8>,
+    idle_timeout: std::time::Duration,
+    timeout: std::time::Duration,
+    max_body_size: usize,
+) -> Result<(String, String, Vec<(String, String)>, Vec<u8>, Vec<u8>), String> {
+    if carry.is_empty() {
+        let mut chunk = [0u8; 1024];
+        let read = async { stream.read(&mut chunk).await.map_err(|e| format!("read error: {e}")) };
+        let n = futures_lite::future::or(read, async {
+            async_io::Timer::after(idle_timeout).await;
+            Err(format!("connection was idle for {}ms", idle_timeout.as_millis()))
+        })
+        .await?;
+        if n == 0 {
+            return Err("connection closed".into());
+        }
+        carry.extend_from_slice(&chunk[..n]);
+    }
+    futures_lite::future::or(read_http_request(stream, carry, max_body_size), async {
+        async_io::Timer::after(timeout).await;
+        Err(format!("client did not send a complete request within {}ms", timeout.as_millis()))
+    })
+    .await
+}
+
 async fn read_http_request(
     stream: &mut async_net::TcpStream,
     carry: Vec<u8>,
+    max_body_size: usize,
 ) -> Result<(String, String, Vec<(String, String)>, Vec<u8>, Vec<u8>), String> {
     let mut buf = carry;
     let header_end;
@@ -643,7 +2375,6 @@ async fn read_http_request(
         .map(|h| (h.name.to_ascii_lowercase(), String::from_utf8_lossy(h.value).to_string()))
         .collect();
 
-    const MAX_BODY_SIZE: usize = 4 * 1024 * 1024;
     let cl_values: Vec<&str> = parsed_headers
         .iter()
         .filter(|(k, _)| k == "content-length")
@@ -653,8 +2384,10 @@ async fn read_http_request(
         return Err("conflicting Content-Length headers".into());
     }
     let content_length: usize = cl_values.first().and_then(|v| v.parse().ok()).unwrap_or(0);
-    if content_length > MAX_BODY_SIZE {
-        return Err(format!("body too large: {content_length} bytes (max {MAX_BODY_SIZE})"));
+    if content_length > max_body_size {
+        return Err(format!(
+            "body too large: {content_length} bytes (limit is {max_body_size} bytes, configurable via SLINT_MCP_MAX_BODY_MB)"
+        ));
     }
 
     let body_start = header_end + 4;
@@ -724,15 +2457,26 @@ fn wants_close(headers: &[(String, String)]) -> bool {
     headers.iter().any(|(k, v)| k == "connection" && v.eq_ignore_ascii_case("close"))
 }
 
-async fn handle_connection(state: &IntrospectionState, mut stream: async_net::TcpStream) {
+async fn handle_connection(
+    state: &IntrospectionState,
+    config: &ServerConfig,
+    mut stream: async_net::TcpStream,
+) {
     let mut carry = Vec::new();
 
     loop {
-        let (method, path, headers, body, leftover) =
-            match read_http_request(&mut stream, carry).await {
-                Ok(req) => req,
-                Err(_) => return,
-            };
+        let (method, path, headers, body, leftover) = match read_http_request_with_timeout(
+            &mut stream,
+            carry,
+            config.idle_timeout,
+            config.request_timeout,
+            config.max_body_size,
+        )
+        .await
+        {
+            Ok(req) => req,
+            Err(_) => return,
+        };
 
         let close_after = wants_close(&headers);
         carry = leftover;
@@ -835,7 +2579,7 @@ async fn handle_connection(state: &IntrospectionState, mut stream: async_net::Tc
     }
 }
 
-async fn run_server(state: Rc<IntrospectionState>, port: u16) {
+async fn run_http_server(state: Rc<IntrospectionState>, config: Rc<ServerConfig>, port: u16) {
     let addr = format!("127.0.0.1:{port}");
     let listener = match async_net::TcpListener::bind(&addr).await {
         Ok(l) => l,
@@ -851,11 +2595,12 @@ async fn run_server(state: Rc<IntrospectionState>, port: u16) {
             Ok((stream, _peer)) => {
                 stream.set_nodelay(true).ok();
                 let state = state.clone();
+                let config = config.clone();
                 let _ = i_slint_core::with_global_context(
                     || panic!("uninitialized platform"),
                     |context| {
                         let _ = context.spawn_local(async move {
-                            handle_connection(&state, stream).await;
+                            handle_connection(&state, &config, stream).await;
                         });
                     },
                 );
@@ -873,19 +2618,20 @@ async fn run_server(state: Rc<IntrospectionState>, port: u16) {
 
 thread_local! {
     static INIT_INSTALLED: std::cell::Cell<bool> = const { std::cell::Cell::new(false) };
+    /// The screenshot directory of the running server, see [`ServerConfig::screenshot_dir`].
+    static SCREENSHOT_DIR: std::cell::RefCell<std::path::PathBuf> =
+        std::cell::RefCell::new(default_screenshot_dir());
+    /// The transport of the running server, reported by get_server_info.
+    static TRANSPORT: std::cell::Cell<Option<Transport>> = const { std::cell::Cell::new(None) };
+    /// The open `SLINT_MCP_LOG_FILE`, see [`log_exchange`].
+    static REQUEST_LOG: std::cell::RefCell<Option<std::fs::File>> =
+        const { std::cell::RefCell::new(None) };
 }
 
 pub fn init() -> Result<(), EventLoopError> {
-    let Ok(port_str) = std::env::var("SLINT_MCP_PORT") else {
+    let Some(config) = ServerConfig::from_env() else {
         return Ok(());
     };
-    let port: u16 = match port_str.parse() {
-        Ok(p) => p,
-        Err(_) => {
-            eprintln!("SLINT_MCP_PORT: invalid port number '{port_str}'");
-            return Ok(());
-        }
-    };
 
     if INIT_INSTALLED.with(|installed| installed.get()) {
         return Ok(());
@@ -893,6 +2639,15 @@ pub fn init() -> Result<(), EventLoopError> {
 
     introspection::ensure_window_tracking()?;
     let state = introspection::shared_state();
+    SCREENSHOT_DIR.with(|dir| *dir.borrow_mut() = config.screenshot_dir.clone());
+    TRANSPORT.with(|transport| transport.set(Some(config.transport)));
+    if let Some(path) = &config.log_file {
+        match std::fs::OpenOptions::new().create(true).append(true).open(path) {
+            Ok(file) => REQUEST_LOG.with(|log| *log.borrow_mut() = Some(file)),
+            Err(e) => eprintln!("SLINT_MCP_LOG_FILE: cannot open {}: {e}", path.display()),
+        }
+    }
+    let config = Rc::new(config);
 
     // The JoinHandle is kept alive inside the OnceCell so the server task is not dropped.
     let server_started =
@@ -918,9 +2673,10 @@ pub fn init() -> Result<(), EventLoopError> {
         }
 
         let state = state_clone.clone();
+        let config = config.clone();
         let spawn_result = i_slint_core::with_global_context(
             || panic!("uninitialized platform"),
-            |context| context.spawn_local(async move { run_server(state, port).await }),
+            |context| context.spawn_local(async move { run_server(state, config).await }),
         );
         match spawn_result {
             Ok(Ok(join_handle)) => {
@@ -982,6 +2738,28 @@ fn test_mcp_rejects_noncanonical_handle() {
         assert!(resp["result"]["content"][0]["text"].as_str().unwrap().contains("Invalid handle"));
     }
 
+    #[test]
+    fn test_validate_handle_arguments() {
+        let check = |args: &str| validate_handle_arguments(&serde_json::from_str(args).unwrap());
+        assert!(check(r#"{"windowHandle":{"index":"1","generation":2}}"#).is_ok());
+        assert!(check(r#"{"windowHandle":{}}"#).is_ok());
+        assert!(check(r#"{"elementHandles":[{"index":"1"}],"maxElements":-1}"#).is_ok());
+
+        assert_eq!(
+            check(r#"{"windowHandle":{"index":-3}}"#).unwrap_err(),
+            "windowHandle index must be a non-negative integer, got -3"
+        );
+        assert_eq!(
+            check(r#"{"elementHandle":{"index":"1","generation":1.5}}"#).unwrap_err(),
+            "elementHandle generation must be an integer, not a fractional number, got 1.5"
+        );
+        assert_eq!(
+            check(r#"{"elementHandles":[{"index":"1"},{"index":"abc"}]}"#).unwrap_err(),
+            "elementHandles index must be an integer (as a number or a decimal string), got \"abc\""
+        );
+        assert!(check(r#"{"windowHandle":"0"}"#).unwrap_err().contains("must be an object"));
+    }
+
     #[test]
     fn test_validate_origin() {
         assert_eq!(validate_origin(&[]), Ok(None));
@@ -1022,6 +2800,119 @@ fn test_find_header_end() {
         assert_eq!(find_header_end(b"\r\n\r\n"), Some(0));
     }
 
+    #[test]
+    fn test_read_http_request_times_out_on_stalled_client() {
+        block_on(async {
+            let listener = async_net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let addr = listener.local_addr().unwrap();
+            let mut client = async_net::TcpStream::connect(addr).await.unwrap();
+            let (mut server, _) = listener.accept().await.unwrap();
+
+            // Send an incomplete request and then stall.
+            client.write_all(b"POST /mcp HTTP/1.1\r\nContent-Length: 100\r\n").await.unwrap();
+
+            let err = read_http_request_with_timeout(
+                &mut server,
+                Vec::new(),
+                std::time::Duration::from_secs(10),
+                std::time::Duration::from_millis(50),
+                1024,
+            )
+            .await
+            .unwrap_err();
+            assert!(err.contains("within 50ms"), "got: {err}");
+        });
+    }
+
+    #[test]
+    fn test_read_http_request_idle_timeout() {
+        block_on(async {
+            let listener = async_net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let addr = listener.local_addr().unwrap();
+            let mut client = async_net::TcpStream::connect(addr).await.unwrap();
+            let (mut server, _) = listener.accept().await.unwrap();
+
+            // A request that starts after longer than the request timeout is still read, as the
+            // wait for its first byte only counts against the idle timeout.
+            let send_late = async {
+                async_io::Timer::after(std::time::Duration::from_millis(150)).await;
+                client.write_all(b"GET /mcp HTTP/1.1\r\n\r\n").await.unwrap();
+            };
+            let read = read_http_request_with_timeout(
+                &mut server,
+                Vec::new(),
+                std::time::Duration::from_secs(10),
+                std::time::Duration::from_millis(50),
+                1024,
+            );
+            let ((), result) = futures_lite::future::zip(send_late, read).await;
+            let (method, path, ..) = result.unwrap();
+            assert_eq!((method.as_str(), path.as_str()), ("GET", "/mcp"));
+
+            // Without a next request, the connection is dropped after the idle timeout.
+            let err = read_http_request_with_timeout(
+                &mut server,
+                Vec::new(),
+                std::time::Duration::from_millis(50),
+                std::time::Duration::from_secs(10),
+                1024,
+            )
+            .await
+            .unwrap_err();
+            assert!(err.contains("idle for 50ms"), "got: {err}");
+        });
+    }
+
+    #[test]
+    fn test_read_http_request_rejects_oversized_body() {
+        block_on(async {
+            let listener = async_net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let addr = listener.local_addr().unwrap();
+            let mut client = async_net::TcpStream::connect(addr).await.unwrap();
+            let (mut server, _) = listener.accept().await.unwrap();
+
+            client.write_all(b"POST /mcp HTTP/1.1\r\nContent-Length: 1025\r\n\r\n").await.unwrap();
+            let err = read_http_request(&mut server, Vec::new(), 1024).await.unwrap_err();
+            assert!(
+                err.contains("1025 bytes") && err.contains("limit is 1024 bytes"),
+                "got: {err}"
+            );
+
+            let body = br#"{"jsonrpc":"2.0"}"#;
+            let request = format!("POST /mcp HTTP/1.1\r\nContent-Length: {}\r\n\r\n", body.len());
+            let bytes = [request.as_bytes(), body].concat();
+            let mut client = async_net::TcpStream::connect(addr).await.unwrap();
+            let (mut server, _) = listener.accept().await.unwrap();
+            client.write_all(&bytes).await.unwrap();
+            let (_, _, _, read_body, _) =
+                read_http_request(&mut server, Vec::new(), body.len()).await.unwrap();
+            assert_eq!(read_body, body);
+        });
+    }
+
+    #[test]
+    fn test_stdio_message() {
+        let state = make_state();
+        let line = block_on(handle_stdio_message(
+            &state,
+            r#"{"jsonrpc":"2.0","id":1,"method":"initialize","params":{}}"#,
+        ))
+        .expect("initialize should produce a response line");
+        assert!(!line.contains('\n'));
+        let resp: Value = serde_json::from_str(&line).unwrap();
+        assert_eq!(resp["id"], 1);
+        assert!(resp["result"]["capabilities"]["tools"].is_object());
+
+        assert!(
+            block_on(handle_stdio_message(
+                &state,
+                r#"{"jsonrpc":"2.0","method":"notifications/initialized"}"#
+            ))
+            .is_none()
+        );
+        assert!(block_on(handle_stdio_message(&state, "  ")).is_none());
+    }
+
     #[test]
     fn test_mcp_initialize() {
         let state = make_state();
@@ -1033,6 +2924,170 @@ fn test_mcp_initialize() {
         assert_eq!(resp["jsonrpc"], "2.0");
         assert!(resp["result"]["protocolVersion"].as_str().is_some());
         assert!(resp["result"]["capabilities"]["tools"].is_object());
+        assert!(resp["result"]["capabilities"]["resources"].is_object());
+        assert!(resp["result"]["capabilities"]["prompts"].is_object());
+        assert_eq!(resp["result"]["serverInfo"]["version"], env!("CARGO_PKG_VERSION"));
+    }
+
+    #[test]
+    fn test_mcp_get_server_info() {
+        let state = make_state();
+        let resp = block_on(handle_mcp_request(
+            &state,
+            r#"{"jsonrpc":"2.0","id":1,"method":"tools/call","params":{"name":"get_server_info","arguments":{}}}"#,
+        ))
+        .unwrap();
+        let text = resp["result"]["content"][0]["text"].as_str().unwrap();
+        let result: Value = serde_json::from_str(text).unwrap();
+        assert_eq!(result["name"], SERVER_NAME);
+        assert_eq!(result["version"], env!("CARGO_PKG_VERSION"));
+        assert_eq!(result["toolCount"], TOOLS.len());
+        // No server was started, so there is no transport to report.
+        assert_eq!(result["transport"], Value::Null);
+        assert_eq!(result["port"], Value::Null);
+
+        TRANSPORT.with(|transport| transport.set(Some(Transport::Http { port: 8080 })));
+        let resp = block_on(handle_mcp_request(
+            &state,
+            r#"{"jsonrpc":"2.0","id":2,"method":"tools/call","params":{"name":"get_server_info","arguments":{}}}"#,
+        ))
+        .unwrap();
+        TRANSPORT.with(|transport| transport.set(None));
+        let text = resp["result"]["content"][0]["text"].as_str().unwrap();
+        let result: Value = serde_json::from_str(text).unwrap();
+        assert_eq!(result["transport"], "http");
+        assert_eq!(result["port"], 8080);
+    }
+
+    #[test]
+    fn test_mcp_ping() {
+        let state = make_state();
+        let resp =
+            block_on(handle_mcp_request(&state, r#"{"jsonrpc":"2.0","id":1,"method":"ping"}"#))
+                .unwrap();
+        assert_eq!(resp["result"], serde_json::json!({}));
+
+        let resp = block_on(handle_mcp_request(
+            &state,
+            r#"{"jsonrpc":"2.0","id":2,"method":"tools/call","params":{"name":"ping","arguments":{}}}"#,
+        ))
+        .unwrap();
+        let text = resp["result"]["content"][0]["text"].as_str().unwrap();
+        let result: Value = serde_json::from_str(text).unwrap();
+        assert_eq!(result["connected"], true);
+        assert_eq!(result["windowCount"], 0);
+        assert!(result["latencyMs"].as_f64().unwrap() >= 0.);
+    }
+
+    #[test]
+    fn test_window_resource_uri() {
+        let handle = proto::Handle { index: 1, generation: 1 };
+        assert_eq!(window_resource_uri(handle), "slint://window/1/1");
+        let index = parse_window_resource_uri("slint://window/1/1").unwrap();
+        assert_eq!(index_to_handle(index), handle);
+
+        for uri in [
+            "slint://window/1",
+            "slint://window/a/1",
+            "slint://element/1/1",
+            "slint://window/1/1/2",
+        ] {
+            assert!(parse_window_resource_uri(uri).is_err(), "{uri} should be rejected");
+        }
+    }
+
+    #[test]
+    fn test_mcp_resources() {
+        let state = make_state();
+        let resp = block_on(handle_mcp_request(
+            &state,
+            r#"{"jsonrpc":"2.0","id":1,"method":"resources/list"}"#,
+        ))
+        .unwrap();
+        assert_eq!(resp["result"]["resources"], serde_json::json!([]));
+
+        let resp = block_on(handle_mcp_request(
+            &state,
+            r#"{"jsonrpc":"2.0","id":2,"method":"resources/read","params":{"uri":"slint://window/1/1"}}"#,
+        ))
+        .unwrap();
+        assert_eq!(resp["error"]["code"], -32002);
+
+        let resp = block_on(handle_mcp_request(
+            &state,
+            r#"{"jsonrpc":"2.0","id":3,"method":"resources/read","params":{}}"#,
+        ))
+        .unwrap();
+        assert_eq!(resp["error"]["code"], -32602);
+    }
+
+    #[test]
+    fn test_mcp_request_log() {
+        let path = std::env::temp_dir()
+            .join(format!("slint-mcp-request-log-test-{}.jsonl", std::process::id()));
+        let file = std::fs::File::create(&path).unwrap();
+        REQUEST_LOG.with(|log| *log.borrow_mut() = Some(file));
+        let state = make_state();
+        block_on(handle_mcp_request(
+            &state,
+            r#"{"jsonrpc":"2.0","id":1,"method":"tools/call","params":{"name":"list_windows","arguments":{}}}"#,
+        ))
+        .unwrap();
+        block_on(handle_mcp_request(&state, "not json")).unwrap();
+        REQUEST_LOG.with(|log| *log.borrow_mut() = None);
+
+        let contents = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        let entries: Vec<Value> =
+            contents.lines().map(|line| serde_json::from_str(line).unwrap()).collect();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0]["request"]["params"]["name"], "list_windows");
+        assert_eq!(entries[0]["requestType"], "RequestWindowListMessage");
+        assert_eq!(entries[0]["response"]["id"], 1);
+        assert!(entries[0]["timestampMs"].as_u64().unwrap() > 0);
+        assert_eq!(entries[1]["request"], "not json");
+        assert_eq!(entries[1]["requestType"], Value::Null);
+        assert_eq!(entries[1]["response"]["error"]["code"], -32700);
+    }
+
+    #[test]
+    fn test_mcp_prompts() {
+        let state = make_state();
+        let resp = block_on(handle_mcp_request(
+            &state,
+            r#"{"jsonrpc":"2.0","id":1,"method":"prompts/list"}"#,
+        ))
+        .unwrap();
+        let names: Vec<_> = resp["result"]["prompts"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|prompt| prompt["name"].as_str().unwrap())
+            .collect();
+        assert_eq!(names, ["inspect_window", "debug_ui"]);
+
+        let resp = block_on(handle_mcp_request(
+            &state,
+            r#"{"jsonrpc":"2.0","id":2,"method":"prompts/get","params":{"name":"debug_ui","arguments":{"problem":"the button is stuck"}}}"#,
+        ))
+        .unwrap();
+        let text = resp["result"]["messages"][0]["content"]["text"].as_str().unwrap();
+        assert!(text.contains("problem in the running Slint application: the button is stuck"));
+        assert!(!text.contains('{'), "unexpanded placeholder in {text}");
+
+        let resp = block_on(handle_mcp_request(
+            &state,
+            r#"{"jsonrpc":"2.0","id":3,"method":"prompts/get","params":{"name":"debug_ui"}}"#,
+        ))
+        .unwrap();
+        assert_eq!(resp["error"]["code"], -32602);
+
+        let resp = block_on(handle_mcp_request(
+            &state,
+            r#"{"jsonrpc":"2.0","id":4,"method":"prompts/get","params":{"name":"nope"}}"#,
+        ))
+        .unwrap();
+        assert_eq!(resp["error"]["code"], -32602);
     }
 
     #[test]
@@ -1045,6 +3100,19 @@ fn test_mcp_notification_returns_none() {
         assert!(resp.is_none());
     }
 
+    #[test]
+    fn test_mcp_request_without_id_is_rejected() {
+        let state = make_state();
+        let resp = block_on(handle_mcp_request(
+            &state,
+            r#"{"jsonrpc":"2.0","method":"tools/call","params":{"name":"list_windows"}}"#,
+        ))
+        .expect("a request without an id should produce an error response");
+        assert_eq!(resp["error"]["code"], -32600);
+        assert!(resp["id"].is_null());
+        assert!(resp.get("result").is_none());
+    }
+
     #[test]
     fn test_mcp_tools_list() {
         let state = make_state();
@@ -1087,6 +3155,283 @@ fn test_mcp_tools_call_unknown_tool() {
         assert!(resp["result"]["isError"].as_bool().unwrap_or(false));
     }
 
+    #[test]
+    fn test_mcp_wait_for_element_invalid_window() {
+        // An invalid window handle must fail immediately instead of polling until the timeout.
+        let state = make_state();
+        let resp = block_on(handle_mcp_request(
+            &state,
+            r#"{"jsonrpc":"2.0","id":7,"method":"tools/call","params":{"name":"wait_for_element","arguments":{"windowHandle":{"index":"1","generation":"1"},"elementsId":"App::button","timeoutMs":60000}}}"#,
+        ));
+        let resp = resp.unwrap();
+        assert!(resp["result"]["isError"].as_bool().unwrap_or(false));
+        assert!(
+            resp["result"]["content"][0]["text"]
+                .as_str()
+                .unwrap()
+                .contains("Invalid window handle")
+        );
+    }
+
+    #[test]
+    fn test_key_sequence_events() {
+        use i_slint_core::input::key_codes;
+        use i_slint_core::platform::WindowEvent;
+        let step = |key: &str, modifiers: &[&str]| proto::KeySequenceStep {
+            key: key.into(),
+            modifiers: modifiers.iter().map(|m| m.to_string()).collect(),
+        };
+        let pressed = |c: char| WindowEvent::KeyPressed { text: c.into() };
+        let released = |c: char| WindowEvent::KeyReleased { text: c.into() };
+
+        let events =
+            key_sequence_events(&[step("a", &["ctrl", "Shift"]), step("Delete", &[])]).unwrap();
+        assert_eq!(
+            events,
+            vec![
+                pressed(key_codes::Control),
+                pressed(key_codes::Shift),
+                pressed('a'),
+                released('a'),
+                released(key_codes::Shift),
+                released(key_codes::Control),
+                pressed(key_codes::Delete),
+                released(key_codes::Delete),
+            ]
+        );
+
+        // Both the Slint and the W3C names are accepted.
+        assert_eq!(named_key("Return"), Some(key_codes::Return));
+        assert_eq!(named_key("Enter"), Some(key_codes::Return));
+        assert_eq!(named_key("ArrowLeft"), Some(key_codes::LeftArrow));
+        assert_eq!(named_key("a"), None);
+
+        let err = key_sequence_events(&[step("a", &["hyper"])]).unwrap_err();
+        assert!(err.contains("step 0") && err.contains("'hyper'"), "unexpected error: {err}");
+        assert!(key_sequence_events(&[step("", &[])]).is_err());
+    }
+
+    #[test]
+    fn test_screenshot_mime_type() {
+        assert_eq!(screenshot_mime_type("").unwrap(), "image/png");
+        assert_eq!(screenshot_mime_type("png").unwrap(), "image/png");
+        assert_eq!(screenshot_mime_type("jpeg").unwrap(), "image/jpeg");
+        assert_eq!(screenshot_mime_type("JPG").unwrap(), "image/jpeg");
+        assert_eq!(screenshot_mime_type("image/webp").unwrap(), "image/webp");
+        let err = screenshot_mime_type("gif").unwrap_err();
+        assert!(err.contains("'gif'"), "unexpected error: {err}");
+    }
+
+    #[test]
+    fn test_screenshot_file_name() {
+        assert_eq!(screenshot_file_name("main", "image/png").unwrap(), "main.png");
+        assert_eq!(screenshot_file_name("main.PNG", "image/png").unwrap(), "main.PNG");
+        assert_eq!(screenshot_file_name("main.png", "image/jpeg").unwrap(), "main.png.jpg");
+        assert!(screenshot_file_name("", "image/webp").unwrap().ends_with(".webp"));
+        for name in ["../escape", "dir/file", "dir\\file", "..", ".hidden", "/etc/passwd"] {
+            assert!(screenshot_file_name(name, "image/png").is_err(), "accepted {name}");
+        }
+    }
+
+    #[test]
+    fn test_mcp_element_properties_batch_reports_per_entry_errors() {
+        let state = make_state();
+        let response = block_on(handle_mcp_request(
+            &state,
+            r#"{"jsonrpc":"2.0","id":1,"method":"tools/call","params":{"name":"get_element_properties_batch","arguments":{"elementHandles":[{"index":"1","generation":"1"},{"index":"2","generation":"1"}]}}}"#,
+        ))
+        .unwrap();
+        assert!(response.get("error").is_none(), "batch should not fail as a whole: {response}");
+        let text = response["result"]["content"][0]["text"].as_str().unwrap();
+        let entries: Value = serde_json::from_str(text).unwrap();
+        let entries = entries.as_array().unwrap();
+        assert_eq!(entries.len(), 2);
+        for (entry, index) in entries.iter().zip(["1", "2"]) {
+            assert_eq!(entry["handle"]["index"], index);
+            assert!(entry["error"].as_str().unwrap().contains("Invalid element handle"));
+        }
+    }
+
+    #[test]
+    fn test_mcp_find_elements_by_role_rejects_unknown_role() {
+        let state = make_state();
+        let resp = block_on(handle_mcp_request(
+            &state,
+            r#"{"jsonrpc":"2.0","id":1,"method":"tools/call","params":{"name":"find_elements_by_role","arguments":{"windowHandle":{"index":"1","generation":"1"},"accessibleRole":"Buttton"}}}"#,
+        ))
+        .unwrap();
+        assert!(resp["result"]["isError"].as_bool().unwrap_or(false));
+        let text = resp["result"]["content"][0]["text"].as_str().unwrap();
+        assert!(text.contains("Buttton"), "unexpected error: {text}");
+    }
+
+    #[test]
+    fn test_mcp_tool_error_codes() {
+        let state = make_state();
+        let call = |arguments: &str| {
+            let body = format!(
+                r#"{{"jsonrpc":"2.0","id":1,"method":"tools/call","params":{{"name":"get_window_properties","arguments":{arguments}}}}}"#
+            );
+            let resp = block_on(handle_mcp_request(&state, &body)).unwrap();
+            assert!(resp["result"]["isError"].as_bool().unwrap_or(false));
+            let error = &resp["result"]["structuredContent"]["error"];
+            assert_eq!(
+                resp["result"]["content"][0]["text"].as_str().unwrap(),
+                format!("Error: {}", error["message"].as_str().unwrap())
+            );
+            error["code"].as_str().unwrap().to_string()
+        };
+
+        assert_eq!(call("{}"), "bad_argument");
+        assert_eq!(call(r#"{"windowHandle":{"index":-1}}"#), "bad_argument");
+        assert_eq!(call(r#"{"windowHandle":{"index":"1","generation":"1"}}"#), "stale_handle");
+    }
+
+    #[test]
+    fn test_mcp_element_tree_fields_and_invisible() {
+        crate::init_no_event_loop();
+        slint::slint! {
+            export component App inherits Window {
+                width: 100px;
+                height: 100px;
+                visible-rect := Rectangle { width: 10px; height: 10px; accessible-role: button; }
+                transparent := Rectangle { width: 10px; height: 10px; opacity: 0; }
+                empty := Rectangle { width: 0px; height: 10px; }
+            }
+        }
+        let app = App::new().unwrap();
+        let state = make_state();
+        let root = state.element_to_handle(crate::ElementRoot::root_element(&app));
+        let handle = serde_json::to_string(&index_to_handle(root)).unwrap();
+        let tree = |extra: &str| {
+            let body = format!(
+                r#"{{"jsonrpc":"2.0","id":1,"method":"tools/call","params":{{"name":"get_element_tree","arguments":{{"elementHandle":{handle}{extra}}}}}}}"#
+            );
+            let resp = block_on(handle_mcp_request(&state, &body)).unwrap();
+            let text = resp["result"]["content"][0]["text"].as_str().unwrap();
+            serde_json::from_str::<Value>(text).unwrap()["elements"].as_array().unwrap().clone()
+        };
+        let ids = |elements: &[Value]| -> Vec<String> {
+            elements
+                .iter()
+                .filter_map(|e| e["typeNamesAndIds"][0]["id"].as_str().map(str::to_string))
+                .collect()
+        };
+
+        let all = tree("");
+        for id in ["App::visible-rect", "App::transparent", "App::empty"] {
+            assert!(ids(&all).contains(&id.to_string()), "{id} missing");
+        }
+        let visible = tree(r#","includeInvisible":false"#);
+        assert!(ids(&visible).contains(&"App::visible-rect".to_string()));
+        assert!(!ids(&visible).contains(&"App::transparent".to_string()));
+        assert!(!ids(&visible).contains(&"App::empty".to_string()));
+
+        let filtered = tree(r#","fields":["accessibleRole"]"#);
+        assert_eq!(filtered.len(), all.len());
+        let allowed = ["accessibleRole", "handle", "parentHandle"];
+        for element in &filtered {
+            let keys: Vec<&String> = element.as_object().unwrap().keys().collect();
+            assert!(keys.iter().all(|key| allowed.contains(&key.as_str())), "{keys:?}");
+        }
+        assert!(filtered.iter().any(|e| e["accessibleRole"] == "Button"));
+
+        // snake_case keys select the same properties as their camelCase spelling.
+        let snake_case = tree(r#","fields":["accessible_role","type_names_and_ids"]"#);
+        let camel_case = tree(r#","fields":["accessibleRole","typeNamesAndIds"]"#);
+        assert_eq!(snake_case.len(), camel_case.len());
+        for (snake, camel) in snake_case.iter().zip(&camel_case) {
+            let keys =
+                |element: &Value| element.as_object().unwrap().keys().cloned().collect::<Vec<_>>();
+            assert_eq!(keys(snake), keys(camel));
+        }
+        assert!(snake_case.iter().any(|e| e["accessibleRole"] == "Button"));
+        assert!(ids(&snake_case).contains(&"App::visible-rect".to_string()));
+
+        let body = format!(
+            r#"{{"jsonrpc":"2.0","id":1,"method":"tools/call","params":{{"name":"get_element_tree","arguments":{{"elementHandle":{handle},"fields":["accessibleRole","type_info"]}}}}}}"#
+        );
+        let resp = block_on(handle_mcp_request(&state, &body)).unwrap();
+        assert_eq!(resp["result"]["isError"], true);
+        assert_eq!(resp["result"]["structuredContent"]["error"]["code"], "bad_argument");
+        let message = resp["result"]["structuredContent"]["error"]["message"].as_str().unwrap();
+        assert!(message.contains("unknown field 'type_info'"), "got: {message}");
+        assert!(message.contains("typeNamesAndIds"), "got: {message}");
+    }
+
+    #[test]
+    fn test_mcp_element_tree_streaming_matches_document() {
+        crate::init_no_event_loop();
+        slint::slint! {
+            export component App inherits Window {
+                outer := Rectangle {
+                    a := Text { text: "a"; }
+                    inner := Rectangle {
+                        b := Text { text: "b"; }
+                    }
+                }
+                c := Text { text: "c"; }
+            }
+        }
+        let app = App::new().unwrap();
+        let state = make_state();
+        let root = state.element_to_handle(crate::ElementRoot::root_element(&app));
+        let handle = serde_json::to_string(&index_to_handle(root)).unwrap();
+        let tree_text = |streaming: bool| {
+            let body = format!(
+                r#"{{"jsonrpc":"2.0","id":1,"method":"tools/call","params":{{"name":"get_element_tree","arguments":{{"elementHandle":{handle},"streaming":{streaming}}}}}}}"#
+            );
+            let resp = block_on(handle_mcp_request(&state, &body)).unwrap();
+            resp["result"]["content"][0]["text"].as_str().unwrap().to_string()
+        };
+
+        // Rebuilds the nesting from parentHandle, as (id, id of the parent) pairs, where the
+        // root is "<root>" and elements without an id are "". Every call hands out fresh
+        // handles for the descendants, so handles can only be compared within one result.
+        let structure = |records: &[Value]| -> Vec<(String, String)> {
+            let id = |index: usize| {
+                let id = records[index]["typeNamesAndIds"][0]["id"].as_str();
+                if index == 0 { "<root>" } else { id.unwrap_or_default() }.to_string()
+            };
+            assert!(records[0].get("parentHandle").is_none(), "the root has no parent");
+            (1..records.len())
+                .map(|index| {
+                    let record = &records[index];
+                    let parent = records[..index]
+                        .iter()
+                        .position(|candidate| candidate["handle"] == record["parentHandle"])
+                        .unwrap_or_else(|| panic!("parent of {record} is not listed before it"));
+                    (id(index), id(parent))
+                })
+                .collect()
+        };
+
+        let document: Value = serde_json::from_str(&tree_text(false)).unwrap();
+        let ndjson = tree_text(true);
+        let mut records: Vec<Value> =
+            ndjson.lines().map(|line| serde_json::from_str(line).unwrap()).collect();
+        let summary = records.pop().unwrap();
+
+        // The root is reported with the handle that was passed in.
+        assert_eq!(records[0]["handle"], serde_json::from_str::<Value>(&handle).unwrap());
+        assert_eq!(document["elements"][0]["handle"], records[0]["handle"]);
+
+        let nesting = structure(&records);
+        let parent_of = |id: &str| {
+            nesting.iter().find(|(child, _)| child == id).map(|(_, parent)| parent.as_str())
+        };
+        assert_eq!(parent_of("App::outer"), Some("<root>"));
+        assert_eq!(parent_of("App::a"), Some("App::outer"));
+        assert_eq!(parent_of("App::inner"), Some("App::outer"));
+        assert_eq!(parent_of("App::b"), Some("App::inner"));
+        assert_eq!(parent_of("App::c"), Some("<root>"));
+
+        assert_eq!(nesting, structure(document["elements"].as_array().unwrap()));
+        assert_eq!(summary["totalCount"], records.len());
+        assert_eq!(summary["totalCount"], document["totalCount"]);
+        assert_eq!(summary["truncated"], document["truncated"]);
+    }
+
     #[test]
     fn test_mcp_unknown_method() {
         let state = make_state();
@@ -1107,14 +3452,31 @@ fn test_mcp_malformed_json() {
     }
 
     #[test]
-    fn test_mcp_batch_request_rejected() {
+    fn test_mcp_rejects_wrong_jsonrpc_version() {
         let state = make_state();
-        let resp = block_on(handle_mcp_request(
-            &state,
-            r#"[{"jsonrpc":"2.0","id":1,"method":"initialize"}]"#,
-        ));
-        let resp = resp.unwrap();
-        assert_eq!(resp["error"]["code"], -32600);
+        for body in [
+            r#"{"jsonrpc":"1.0","id":7,"method":"tools/list"}"#,
+            r#"{"jsonrpc":2.0,"id":7,"method":"tools/list"}"#,
+            r#"{"id":7,"method":"tools/list"}"#,
+        ] {
+            let resp = block_on(handle_mcp_request(&state, body)).unwrap();
+            assert_eq!(resp["error"]["code"], -32600, "{body}");
+            assert_eq!(resp["id"], 7, "{body}");
+        }
+    }
+
+    #[test]
+    fn test_mcp_rejects_batch_request() {
+        let state = make_state();
+        for body in [
+            r#"[{"jsonrpc":"2.0","id":1,"method":"initialize"},{"jsonrpc":"2.0","id":2,"method":"tools/list"}]"#,
+            r#"[{"jsonrpc":"2.0","method":"notifications/initialized"}]"#,
+            "[]",
+        ] {
+            let resp = block_on(handle_mcp_request(&state, body)).unwrap();
+            assert_eq!(resp["error"]["code"], -32600, "{body}");
+            assert_eq!(resp["id"], Value::Null, "{body}");
+        }
     }
 
     #[test]
@@ -1197,6 +3559,7 @@ fn test_proto_serde_field_names_match_tool_schemas() {
         let req = proto::RequestFindElementsById {
             window_handle: Some(proto::Handle { index: 0, generation: 0 }),
             elements_id: "test".into(),
+            ..Default::default()
         };
         let json = serde_json::to_value(req).unwrap();
         assert!(json.get("elementsId").is_some(), "expected camelCase 'elementsId'");
@@ -1225,6 +3588,627 @@ fn test_proto_enum_serde_format() {
         assert_eq!(button, proto::PointerEventButton::Left);
     }
 
+    #[test]
+    fn test_property_condition() {
+        let properties = proto::ElementPropertiesResponse {
+            accessible_label: "Volume".into(),
+            accessible_value: "42".into(),
+            accessible_checked: true,
+            accessible_value_maximum: 100.,
+            size: Some(proto::LogicalSize { width: 80., height: 20. }),
+            accessible_role: proto::AccessibleRole::Slider.into(),
+            ..Default::default()
+        };
+        let check = |property: &str, operator: &str, expected: &str| {
+            PropertyCondition::new(property, operator, expected)
+                .and_then(|condition| condition.check(&properties))
+                .map(|(passed, _)| passed)
+        };
+
+        assert_eq!(check("accessible_label", "eq", "Volume"), Ok(true));
+        assert_eq!(check("accessibleLabel", "eq", "volume"), Ok(false));
+        assert_eq!(check("accessible_label", "neq", "Mute"), Ok(true));
+        assert_eq!(check("accessible_label", "contains", "lum"), Ok(true));
+        assert_eq!(check("accessible_label", "contains", "Mute"), Ok(false));
+        assert_eq!(check("accessible_role", "eq", "Slider"), Ok(true));
+        // Text properties holding numbers compare numerically with gt and lt.
+        assert_eq!(check("accessible_value", "gt", "9"), Ok(true));
+        assert_eq!(check("accessible_value", "lt", "42"), Ok(false));
+        assert_eq!(check("accessible_label", "gt", "1"), Ok(false));
+        assert!(check("accessible_value", "gt", "many").is_err());
+
+        assert_eq!(check("accessible_checked", "eq", "true"), Ok(true));
+        assert_eq!(check("accessible_checked", "neq", "true"), Ok(false));
+        assert!(check("accessible_checked", "eq", "yes").is_err());
+        assert!(check("accessible_checked", "gt", "true").is_err());
+
+        assert_eq!(check("accessible_value_maximum", "eq", "100"), Ok(true));
+        assert_eq!(check("width", "neq", "80"), Ok(false));
+        assert_eq!(check("width", "gt", "79.5"), Ok(true));
+        assert_eq!(check("height", "lt", "20"), Ok(false));
+        assert!(check("width", "contains", "8").is_err());
+        assert!(check("width", "eq", "wide").is_err());
+
+        assert!(check("no_such_property", "eq", "").is_err());
+        assert!(check("accessible_label", "like", "").is_err());
+    }
+
+    #[test]
+    fn test_mcp_assert_element_property() {
+        crate::init_no_event_loop();
+        slint::slint! {
+            export component App inherits Window {
+                check := Rectangle {
+                    accessible-role: checkbox;
+                    accessible-checked: true;
+                }
+            }
+        }
+        let app = App::new().unwrap();
+        let state = make_state();
+        let element = crate::ElementHandle::find_by_element_id(&app, "App::check").next().unwrap();
+        let handle =
+            serde_json::to_string(&index_to_handle(state.element_to_handle(element))).unwrap();
+        let assert_checked = |expected: &str| {
+            let body = format!(
+                r#"{{"jsonrpc":"2.0","id":1,"method":"tools/call","params":{{"name":"assert_element_property","arguments":{{"elementHandle":{handle},"property":"accessible_checked","operator":"eq","expected":"{expected}"}}}}}}"#
+            );
+            block_on(handle_mcp_request(&state, &body)).unwrap()
+        };
+
+        let resp = assert_checked("true");
+        assert!(resp["result"]["isError"].is_null());
+        let text = resp["result"]["content"][0]["text"].as_str().unwrap();
+        let result: Value = serde_json::from_str(text).unwrap();
+        assert_eq!(result["passed"], true);
+        assert_eq!(result["actual"], true);
+
+        let resp = assert_checked("false");
+        assert_eq!(resp["result"]["isError"], true);
+        let text = resp["result"]["content"][0]["text"].as_str().unwrap();
+        let result: Value = serde_json::from_str(text).unwrap();
+        assert_eq!(result["passed"], false);
+        assert_eq!(result["expected"], "false");
+
+        let resp = assert_checked("maybe");
+        assert_eq!(resp["result"]["structuredContent"]["error"]["code"], "bad_argument");
+    }
+
+    #[test]
+    fn test_mcp_poll_until() {
+        crate::init_no_event_loop();
+        slint::slint! {
+            export component App inherits Window {
+                label := Text {
+                    text: "Done";
+                }
+            }
+        }
+        let app = App::new().unwrap();
+        let state = make_state();
+        let element = crate::ElementHandle::find_by_element_id(&app, "App::label").next().unwrap();
+        let handle =
+            serde_json::to_string(&index_to_handle(state.element_to_handle(element))).unwrap();
+        let poll = |operator: &str| {
+            let body = format!(
+                r#"{{"jsonrpc":"2.0","id":1,"method":"tools/call","params":{{"name":"poll_until","arguments":{{"elementHandle":{handle},"property":"accessible_label","operator":"{operator}","expected":"Done"}}}}}}"#
+            );
+            block_on(handle_mcp_request(&state, &body)).unwrap()
+        };
+
+        // An already satisfied condition returns without waiting, which needs no event loop.
+        let resp = poll("eq");
+        let text = resp["result"]["content"][0]["text"].as_str().unwrap();
+        let result: Value = serde_json::from_str(text).unwrap();
+        assert_eq!(result["passed"], true);
+        assert_eq!(result["polls"], 1);
+        assert_eq!(result["actual"], "Done");
+
+        let resp = poll("equals");
+        assert_eq!(resp["result"]["structuredContent"]["error"]["code"], "bad_argument");
+    }
+
+    #[test]
+    fn test_mcp_element_properties_sibling_index_and_z_order() {
+        crate::init_no_event_loop();
+        slint::slint! {
+            export component App inherits Window {
+                Rectangle {}
+                second := Rectangle {}
+            }
+        }
+        let app = App::new().unwrap();
+        let state = make_state();
+        let properties = |element| {
+            let handle = index_to_handle(state.element_to_handle(element));
+            let body = format!(
+                r#"{{"jsonrpc":"2.0","id":1,"method":"tools/call","params":{{"name":"get_element_properties","arguments":{{"elementHandle":{}}}}}}}"#,
+                serde_json::to_string(&handle).unwrap()
+            );
+            let resp = block_on(handle_mcp_request(&state, &body)).unwrap();
+            let text = resp["result"]["content"][0]["text"].as_str().unwrap();
+            serde_json::from_str::<Value>(text).unwrap()
+        };
+
+        let second = properties(
+            crate::ElementHandle::find_by_element_id(&app, "App::second").next().unwrap(),
+        );
+        assert_eq!(second["siblingIndex"], 1);
+        assert_eq!(second["zOrder"], 2);
+
+        // The window root has no parent, so it has no sibling index.
+        let root = properties(crate::ElementRoot::root_element(&app));
+        assert!(root.get("siblingIndex").is_none());
+        assert_eq!(root["zOrder"], 0);
+
+        // Bulk queries leave out zOrder, as it walks the whole window for every element.
+        let handle =
+            index_to_handle(state.element_to_handle(crate::ElementRoot::root_element(&app)));
+        let body = format!(
+            r#"{{"jsonrpc":"2.0","id":1,"method":"tools/call","params":{{"name":"get_element_tree","arguments":{{"elementHandle":{}}}}}}}"#,
+            serde_json::to_string(&handle).unwrap()
+        );
+        let resp = block_on(handle_mcp_request(&state, &body)).unwrap();
+        let text = resp["result"]["content"][0]["text"].as_str().unwrap();
+        let tree = serde_json::from_str::<Value>(text).unwrap();
+        let elements = tree["elements"].as_array().unwrap();
+        assert!(elements.len() >= 3);
+        assert!(elements.iter().all(|element| element.get("zOrder").is_none()));
+    }
+
+    #[test]
+    fn test_mcp_clipboard() {
+        crate::init_no_event_loop();
+        slint::slint! {
+            export component App inherits Window {}
+        }
+        let app = App::new().unwrap();
+        let state = make_state();
+        state.add_window(
+            &i_slint_core::window::WindowInner::from_pub(app.window()).window_adapter(),
+        );
+        let handle = serde_json::to_string(&index_to_handle(state.window_handles()[0])).unwrap();
+        let call = |tool: &str, arguments: &str| {
+            let body = format!(
+                r#"{{"jsonrpc":"2.0","id":1,"method":"tools/call","params":{{"name":"{tool}","arguments":{{"windowHandle":{handle}{arguments}}}}}}}"#
+            );
+            let resp = block_on(handle_mcp_request(&state, &body)).unwrap();
+            let text = resp["result"]["content"][0]["text"].as_str().unwrap();
+            serde_json::from_str::<Value>(text).unwrap()
+        };
+
+        assert_eq!(call("set_clipboard", r#","text":"copied text""#)["applied"], true);
+        assert_eq!(call("get_clipboard", "")["text"], "copied text");
+        // The testing backend has no selection clipboard.
+        assert_eq!(
+            call("set_clipboard", r#","text":"selected","selection":true"#)["applied"],
+            false
+        );
+        assert_eq!(call("get_clipboard", r#","selection":true"#)["text"], Value::Null);
+    }
+
+    #[test]
+    fn test_mcp_list_element_ids() {
+        crate::init_no_event_loop();
+        slint::slint! {
+            export component App inherits Window {
+                header := Text {}
+                Rectangle {
+                    body := Rectangle {}
+                }
+                footer := Text {}
+            }
+        }
+        let app = App::new().unwrap();
+        let state = make_state();
+        state.add_window(
+            &i_slint_core::window::WindowInner::from_pub(app.window()).window_adapter(),
+        );
+        let handle = serde_json::to_string(&index_to_handle(state.window_handles()[0])).unwrap();
+        let list = |arguments: &str| {
+            let body = format!(
+                r#"{{"jsonrpc":"2.0","id":1,"method":"tools/call","params":{{"name":"list_element_ids","arguments":{{"windowHandle":{handle}{arguments}}}}}}}"#
+            );
+            let resp = block_on(handle_mcp_request(&state, &body)).unwrap();
+            let text = resp["result"]["content"][0]["text"].as_str().unwrap();
+            serde_json::from_str::<Value>(text).unwrap()
+        };
+        let ids = |result: &Value| -> Vec<String> {
+            result["elements"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|element| {
+                    assert!(element["handle"].is_object());
+                    element["id"].as_str().unwrap().to_string()
+                })
+                .collect()
+        };
+
+        // The unnamed rectangle has no ID and is left out.
+        let result = list("");
+        assert_eq!(ids(&result), ["App::root", "App::header", "App::body", "App::footer"]);
+        assert_eq!(result["truncated"], false);
+
+        let result = list(r#","maxElements":2"#);
+        assert_eq!(ids(&result), ["App::root", "App::header"]);
+        assert_eq!(result["totalCount"], 2);
+        assert_eq!(result["truncated"], true);
+    }
+
+    #[test]
+    fn test_mcp_pagination() {
+        crate::init_no_event_loop();
+        slint::slint! {
+            export component App inherits Window {
+                for i in 5: item := Rectangle {}
+            }
+        }
+        let app = App::new().unwrap();
+        let state = make_state();
+        state.add_window(
+            &i_slint_core::window::WindowInner::from_pub(app.window()).window_adapter(),
+        );
+        let window = serde_json::to_string(&index_to_handle(state.window_handles()[0])).unwrap();
+        let root = index_to_handle(state.element_to_handle(crate::ElementRoot::root_element(&app)));
+        let root = serde_json::to_string(&root).unwrap();
+        let call = |tool: &str, arguments: String| {
+            let body = format!(
+                r#"{{"jsonrpc":"2.0","id":1,"method":"tools/call","params":{{"name":"{tool}","arguments":{arguments}}}}}"#
+            );
+            let resp = block_on(handle_mcp_request(&state, &body)).unwrap();
+            let text = resp["result"]["content"][0]["text"].as_str().unwrap();
+            serde_json::from_str::<Value>(text).unwrap()
+        };
+        let by_id = |page: &str| {
+            call(
+                "find_elements_by_id",
+                format!(r#"{{"windowHandle":{window},"elementsId":"App::item"{page}}}"#),
+            )
+        };
+        // An empty page omits elementHandles, as protobuf JSON skips empty repeated fields.
+        let page_len = |result: &Value| result["elementHandles"].as_array().map_or(0, Vec::len);
+
+        let result = by_id("");
+        assert_eq!(page_len(&result), 5);
+        assert_eq!(result["total"], 5);
+        assert!(result.get("nextOffset").is_none());
+
+        let result = by_id(r#","limit":2"#);
+        assert_eq!(page_len(&result), 2);
+        assert_eq!(result["total"], 5);
+        assert_eq!(result["nextOffset"], 2);
+
+        let result = by_id(r#","offset":4,"limit":2"#);
+        assert_eq!(page_len(&result), 1);
+        assert!(result.get("nextOffset").is_none());
+
+        let result = by_id(r#","offset":3,"limit":2"#);
+        assert_eq!(page_len(&result), 2);
+        assert!(result.get("nextOffset").is_none());
+
+        let result = by_id(r#","offset":10"#);
+        assert_eq!(page_len(&result), 0);
+        assert_eq!(result["total"], 5);
+
+        // Following nextOffset visits every match exactly once.
+        let first = by_id(r#","limit":3"#);
+        assert_eq!(first["nextOffset"], 3);
+        let second = by_id(r#","offset":3,"limit":3"#);
+        assert_eq!(page_len(&first) + page_len(&second), 5);
+        assert!(second.get("nextOffset").is_none());
+
+        let result = call(
+            "query_element_descendants",
+            format!(
+                r#"{{"elementHandle":{root},"queryStack":[{{"matchDescendants":true}},{{"matchElementId":"App::item"}}],"findAll":true,"offset":1,"limit":3}}"#
+            ),
+        );
+        assert_eq!(page_len(&result), 3);
+        assert_eq!(result["total"], 5);
+        assert_eq!(result["nextOffset"], 4);
+    }
+
+    #[test]
+    fn test_mcp_get_focused_element() {
+        crate::init_no_event_loop();
+        slint::slint! {
+            export component App inherits Window {
+                public function focus-name() {
+                    name.focus();
+                }
+                name := TextInput {}
+            }
+        }
+        let app = App::new().unwrap();
+        let state = make_state();
+        state.add_window(
+            &i_slint_core::window::WindowInner::from_pub(app.window()).window_adapter(),
+        );
+        let handle = serde_json::to_string(&index_to_handle(state.window_handles()[0])).unwrap();
+        let focused = || {
+            let body = format!(
+                r#"{{"jsonrpc":"2.0","id":1,"method":"tools/call","params":{{"name":"get_focused_element","arguments":{{"windowHandle":{handle}}}}}}}"#
+            );
+            let resp = block_on(handle_mcp_request(&state, &body)).unwrap();
+            let text = resp["result"]["content"][0]["text"].as_str().unwrap();
+            serde_json::from_str::<Value>(text).unwrap()
+        };
+
+        assert_eq!(focused(), Value::Null);
+        app.invoke_focus_name();
+        let result = focused();
+        assert_eq!(result["properties"]["typeNamesAndIds"][0]["id"], "App::name");
+        assert!(result["handle"].is_object());
+    }
+
+    #[test]
+    fn test_mcp_set_window_geometry() {
+        crate::init_no_event_loop();
+        slint::slint! {
+            export component App inherits Window {}
+        }
+        let app = App::new().unwrap();
+        let state = make_state();
+        state.add_window(
+            &i_slint_core::window::WindowInner::from_pub(app.window()).window_adapter(),
+        );
+        let handle = serde_json::to_string(&index_to_handle(state.window_handles()[0])).unwrap();
+        let call = |tool: &str, arguments: &str| {
+            let body = format!(
+                r#"{{"jsonrpc":"2.0","id":1,"method":"tools/call","params":{{"name":"{tool}","arguments":{{"windowHandle":{handle},{arguments}}}}}}}"#
+            );
+            let resp = block_on(handle_mcp_request(&state, &body)).unwrap();
+            let text = resp["result"]["content"][0]["text"].as_str().unwrap();
+            serde_json::from_str::<Value>(text).unwrap()
+        };
+
+        let result = call("set_window_size", r#""size":{"width":320,"height":240}"#);
+        assert_eq!(result["applied"], true);
+        assert_eq!(result["size"], serde_json::json!({"width": 320, "height": 240}));
+        assert_eq!(app.window().size(), slint::PhysicalSize::new(320, 240));
+
+        // The testing backend has no notion of a window position, so the move is not applied.
+        let result = call("set_window_position", r#""position":{"x":10,"y":20}"#);
+        assert_eq!(result["applied"], false);
+    }
+
+    #[test]
+    fn test_mcp_compare_screenshots() {
+        let png = |width, height, pixels: &[(u32, u32)]| {
+            let mut image =
+                image::RgbaImage::from_pixel(width, height, image::Rgba([0, 0, 0, 255]));
+            for &(x, y) in pixels {
+                image.put_pixel(x, y, image::Rgba([255, 255, 255, 255]));
+            }
+            let data = introspection::encode_image(image, image::ImageFormat::Png, None).unwrap();
+            base64::engine::general_purpose::STANDARD.encode(data)
+        };
+        let state = make_state();
+        let call = |baseline: &str, candidate: &str| {
+            let body = format!(
+                r#"{{"jsonrpc":"2.0","id":1,"method":"tools/call","params":{{"name":"compare_screenshots","arguments":{{"baseline":"{baseline}","candidate":"{candidate}"}}}}}}"#
+            );
+            let resp = block_on(handle_mcp_request(&state, &body)).unwrap();
+            let text = resp["result"]["content"][0]["text"].as_str().unwrap();
+            serde_json::from_str::<Value>(text).unwrap()
+        };
+
+        let result = call(&png(2, 2, &[]), &png(2, 2, &[(1, 1)]));
+        assert_eq!(result["sizeMatches"], true);
+        assert_eq!(result["differingPixels"], 1);
+        assert_eq!(result["totalPixels"], 4);
+        assert_eq!(result["similarity"], 0.75);
+
+        let result = call(&png(2, 2, &[]), &png(3, 2, &[]));
+        assert_eq!(result["sizeMatches"], false);
+        assert_eq!(result["candidate"], serde_json::json!({"width": 3, "height": 2}));
+    }
+
+    #[test]
+    fn test_mcp_compare_screenshots_baseline_path() {
+        let image = image::RgbaImage::from_pixel(2, 2, image::Rgba([0, 0, 0, 255]));
+        let data = introspection::encode_image(image, image::ImageFormat::Png, None).unwrap();
+        let candidate = base64::engine::general_purpose::STANDARD.encode(&data);
+        let state = make_state();
+        let call = |baseline_path: &str| {
+            let body = format!(
+                r#"{{"jsonrpc":"2.0","id":1,"method":"tools/call","params":{{"name":"compare_screenshots","arguments":{{"baselinePath":"{baseline_path}","candidate":"{candidate}"}}}}}}"#
+            );
+            block_on(handle_mcp_request(&state, &body)).unwrap()
+        };
+        let error = |resp: &Value| {
+            assert_eq!(resp["result"]["isError"], true);
+            assert_eq!(resp["result"]["structuredContent"]["error"]["code"], "bad_argument");
+            resp["result"]["content"][0]["text"].as_str().unwrap().to_string()
+        };
+
+        let dir =
+            std::env::temp_dir().join(format!("slint-mcp-baseline-test-{}", std::process::id()));
+        let previous_dir = SCREENSHOT_DIR.with(|d| d.replace(dir.clone()));
+
+        // The directory doesn't exist yet.
+        let text = error(&call("baseline.png"));
+        assert!(text.contains("no screenshot directory"), "got: {text}");
+
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("baseline.png"), &data).unwrap();
+        let resp = call("baseline.png");
+        let text = resp["result"]["content"][0]["text"].as_str().unwrap();
+        let result: Value = serde_json::from_str(text).unwrap();
+        assert_eq!(result["differingPixels"], 0);
+
+        // Paths that would leave the directory are rejected without touching the file system.
+        let outside = dir.parent().unwrap().join("baseline.png").display().to_string();
+        for path in ["../baseline.png", "..", "sub/baseline.png", outside.as_str()] {
+            let text = error(&call(&path.replace('\\', "\\\\")));
+            assert!(text.contains("invalid baselinePath"), "{path}: got: {text}");
+        }
+
+        SCREENSHOT_DIR.with(|d| *d.borrow_mut() = previous_dir);
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_mcp_set_window_states() {
+        crate::init_no_event_loop();
+        slint::slint! {
+            export component App inherits Window {}
+        }
+        let app = App::new().unwrap();
+        let state = make_state();
+        state.add_window(
+            &i_slint_core::window::WindowInner::from_pub(app.window()).window_adapter(),
+        );
+        let handle = serde_json::to_string(&index_to_handle(state.window_handles()[0])).unwrap();
+        let call = |tool: &str, field: &str, enabled: bool| {
+            let body = format!(
+                r#"{{"jsonrpc":"2.0","id":1,"method":"tools/call","params":{{"name":"{tool}","arguments":{{"windowHandle":{handle},"{field}":{enabled}}}}}}}"#
+            );
+            let resp = block_on(handle_mcp_request(&state, &body)).unwrap();
+            let text = resp["result"]["content"][0]["text"].as_str().unwrap();
+            serde_json::from_str::<Value>(text).unwrap()
+        };
+
+        assert_eq!(call("set_fullscreen", "fullscreen", true)["isFullscreen"], true);
+        assert!(app.window().is_fullscreen());
+        assert_eq!(call("set_fullscreen", "fullscreen", false).get("isFullscreen"), None);
+        assert_eq!(call("set_maximized", "maximized", true)["isMaximized"], true);
+        assert!(app.window().is_maximized());
+        assert_eq!(call("set_minimized", "minimized", true)["isMinimized"], true);
+        assert!(app.window().is_minimized());
+    }
+
+    #[test]
+    fn test_mcp_close_window_reports_veto() {
+        crate::init_no_event_loop();
+        slint::slint! {
+            export component App inherits Window {}
+        }
+        let app = App::new().unwrap();
+        app.show().unwrap();
+        let state = make_state();
+        state.add_window(
+            &i_slint_core::window::WindowInner::from_pub(app.window()).window_adapter(),
+        );
+        let handle = serde_json::to_string(&index_to_handle(state.window_handles()[0])).unwrap();
+        let close = || {
+            let body = format!(
+                r#"{{"jsonrpc":"2.0","id":1,"method":"tools/call","params":{{"name":"close_window","arguments":{{"windowHandle":{handle}}}}}}}"#
+            );
+            let resp = block_on(handle_mcp_request(&state, &body)).unwrap();
+            let text = resp["result"]["content"][0]["text"].as_str().unwrap();
+            serde_json::from_str::<Value>(text).unwrap()["closed"].as_bool().unwrap()
+        };
+
+        app.window().on_close_requested(|| slint::CloseRequestResponse::KeepWindowShown);
+        assert!(!close());
+        assert!(app.window().is_visible());
+
+        app.window().on_close_requested(|| slint::CloseRequestResponse::HideWindow);
+        assert!(close());
+        assert!(!app.window().is_visible());
+    }
+
+    #[test]
+    fn test_accessibility_action_round_trip_all_variants() {
+        // Every action must be advertised in the tool schema and be reachable from its name.
+        let defs = tool_definitions();
+        let tool = defs["tools"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|t| t["name"] == "invoke_accessibility_action")
+            .unwrap();
+        let advertised: Vec<&str> = tool["inputSchema"]["properties"]["action"]["enum"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|v| v.as_str().unwrap())
+            .collect();
+
+        let variants: Vec<proto::ElementAccessibilityAction> =
+            (0..).map_while(|i| proto::ElementAccessibilityAction::try_from(i).ok()).collect();
+        assert_eq!(variants.len(), advertised.len());
+        for action in variants {
+            let name = serde_json::to_value(action).unwrap();
+            assert!(advertised.contains(&name.as_str().unwrap()), "{name} not advertised");
+            let request: proto::RequestInvokeElementAccessibilityAction =
+                serde_json::from_value(serde_json::json!({ "action": name })).unwrap();
+            assert_eq!(request.action(), action);
+        }
+    }
+
+    #[test]
+    fn test_enum_arguments() {
+        assert_eq!(
+            proto::PointerEventButton::from_name("left"),
+            Ok(proto::PointerEventButton::Left)
+        );
+        assert_eq!(
+            proto::PointerEventButton::from_name("Right"),
+            Ok(proto::PointerEventButton::Right)
+        );
+        assert_eq!(
+            proto::PointerEventButton::from_name("x").unwrap_err(),
+            "unknown button 'x'; expected one of Left, Right, Middle, Back, Forward, Other"
+        );
+        assert_eq!(
+            proto::PointerEventButton::from_number(9).unwrap_err(),
+            "unknown button '9'; expected one of Left, Right, Middle, Back, Forward, Other"
+        );
+
+        assert_eq!(
+            proto::ClickAction::from_name("double_click"),
+            Ok(proto::ClickAction::DoubleClick)
+        );
+        assert_eq!(proto::ClickAction::from_number(0), Ok(proto::ClickAction::SingleClick));
+        assert_eq!(
+            proto::ClickAction::from_name("tripleclick").unwrap_err(),
+            "unknown action 'tripleclick'; expected one of SingleClick, DoubleClick"
+        );
+
+        assert_eq!(
+            proto::ElementAccessibilityAction::from_name("default"),
+            Ok(proto::ElementAccessibilityAction::Default)
+        );
+        assert_eq!(
+            proto::ElementAccessibilityAction::from_name("INCREMENT"),
+            Ok(proto::ElementAccessibilityAction::Increment)
+        );
+        assert_eq!(
+            proto::ElementAccessibilityAction::from_name("collapse").unwrap_err(),
+            "unknown action 'collapse'; expected one of Default_, Increment, Decrement, Expand"
+        );
+
+        assert_eq!(proto::KeyEventType::from_name("press"), Ok(proto::KeyEventType::Press));
+        assert_eq!(
+            proto::KeyEventType::from_number(-1).unwrap_err(),
+            "unknown eventType '-1'; expected one of PressAndRelease, Press, Release"
+        );
+    }
+
+    #[test]
+    fn test_mcp_enum_arguments_are_canonicalized() {
+        let args =
+            serde_json::json!({"action": "double_click", "button": "right", "elementHandle": {}});
+        let args = canonicalize_enum_arguments("click_element", &args).unwrap();
+        assert_eq!(args["action"], "DoubleClick");
+        assert_eq!(args["button"], "Right");
+        let request: proto::RequestElementClick = deserialize_params(&args).unwrap();
+        assert_eq!(request.button(), proto::PointerEventButton::Right);
+
+        let state = make_state();
+        let resp = block_on(handle_mcp_request(
+            &state,
+            r#"{"jsonrpc":"2.0","id":1,"method":"tools/call","params":{"name":"click_element","arguments":{"elementHandle":{"index":"1","generation":"1"},"button":"wheel"}}}"#,
+        ))
+        .unwrap();
+        assert_eq!(resp["result"]["structuredContent"]["error"]["code"], "bad_argument");
+        let message = resp["result"]["structuredContent"]["error"]["message"].as_str().unwrap();
+        assert!(message.starts_with("unknown button 'wheel'; expected one of Left"), "{message}");
+    }
+
     #[test]
     fn test_proto_enum_string_deserialization_in_struct() {
         // This is the actual MCP client path: string enum values inside a parent struct.
@@ -3,7 +3,9 @@
 
 use core::ops::ControlFlow;
 use i_slint_core::SharedString;
-use i_slint_core::accessibility::{AccessibilityAction, AccessibleStringProperty};
+use i_slint_core::accessibility::{
+    AccessibilityAction, AccessibleStringProperty, SupportedAccessibilityAction,
+};
 use i_slint_core::api::{ComponentHandle, LogicalPosition};
 use i_slint_core::item_tree::{ItemTreeRc, ItemWeak, ParentItemTraversalMode};
 use i_slint_core::items::{ItemRc, Opacity, PointerEventButton};
@@ -334,6 +336,13 @@ fn collect_elements(item: ItemRc) -> impl Iterator<Item = ElementHandle> {
             .map(move |element_index| ElementHandle { item: item.downgrade(), element_index })
     }
 
+    /// Returns the element that has the keyboard focus in `window`, if any.
+    #[cfg(feature = "mcp")]
+    pub(crate) fn focused(window: &i_slint_core::api::Window) -> Option<Self> {
+        let item = WindowInner::from_pub(window).focus_item.borrow().upgrade()?;
+        Some(ElementHandle { item: item.downgrade(), element_index: 0 })
+    }
+
     /// Visit all descendants of this element and call the visitor to each of them, until the visitor returns [`ControlFlow::Break`].
     /// When the visitor breaks, the function returns the value. If it doesn't break, the function returns None.
     pub fn visit_descendants<R>(
@@ -921,6 +930,96 @@ pub fn invoke_accessible_expand_action(&self) {
         }
     }
 
+    /// Returns the accessibility actions the element declares callbacks for.
+    pub(crate) fn supported_accessibility_actions(&self) -> SupportedAccessibilityAction {
+        if self.element_index != 0 {
+            return SupportedAccessibilityAction::empty();
+        }
+        self.item
+            .upgrade()
+            .map(|item| item.supported_accessibility_actions())
+            .unwrap_or(SupportedAccessibilityAction::empty())
+    }
+
+    /// Returns the index of the element among the children of its parent, or `None` if the
+    /// element is not valid or is the root of its window or popup.
+    pub(crate) fn sibling_index(&self) -> Option<usize> {
+        let item = self.item.upgrade()?;
+        item.parent_item(ParentItemTraversalMode::StopAtPopups)?;
+        Some(
+            core::iter::successors(item.previous_sibling(), |item| item.previous_sibling()).count(),
+        )
+    }
+
+    /// Returns the position of the element in the paint order of its window or popup: an
+    /// element is drawn on top of all elements with a lower value. Returns `None` if the
+    /// element is not valid.
+    pub(crate) fn z_order(&self) -> Option<usize> {
+        let item = self.item.upgrade()?;
+        let mut root = item.clone();
+        while let Some(parent) = root.parent_item(ParentItemTraversalMode::StopAtPopups) {
+            root = parent;
+        }
+        if root == item {
+            return Some(0);
+        }
+        // Children are painted after their parent, in order, and the compiler has already
+        // sorted them by their `z` property.
+        let mut order = 0;
+        root.visit_descendants(|descendant| {
+            order += 1;
+            if *descendant == item { ControlFlow::Break(order) } else { ControlFlow::Continue(()) }
+        })
+    }
+
+    /// Returns the element this element is nested in, or `None` for the root of a window.
+    /// Elements merged into the same item nest in order, and the root of a popup is nested in
+    /// the element that shows it, like [`Self::visit_descendants()`] visits them.
+    #[cfg(feature = "mcp")]
+    pub(crate) fn parent_element(&self) -> Option<Self> {
+        if self.element_index > 0 {
+            return Some(Self { item: self.item.clone(), element_index: self.element_index - 1 });
+        }
+        let mut item = self.item.upgrade()?;
+        loop {
+            item = item.parent_item(ParentItemTraversalMode::FindAllParents)?;
+            if let Some(count) = item.element_count().filter(|count| *count > 0) {
+                return Some(Self { item: item.downgrade(), element_index: count - 1 });
+            }
+        }
+    }
+
+    /// Returns whether `position`, in window coordinates, lies within all ancestors of the
+    /// element in its window or popup that clip their children, such as a `Flickable` or a
+    /// `Rectangle` with `clip: true`. Returns false if the element is not valid.
+    #[cfg(feature = "mcp")]
+    pub(crate) fn is_unclipped_at(&self, position: LogicalPosition) -> bool {
+        let Some(mut item) = self.item.upgrade() else {
+            return false;
+        };
+        while let Some(parent) = item.parent_item(ParentItemTraversalMode::StopAtPopups) {
+            if parent.borrow().as_ref().clips_children() {
+                let geometry = parent.geometry();
+                let origin = parent.map_to_window(geometry.origin);
+                if position.x < origin.x
+                    || position.y < origin.y
+                    || position.x >= origin.x + geometry.width()
+                    || position.y >= origin.y + geometry.height()
+                {
+                    return false;
+                }
+            }
+            item = parent;
+        }
+        true
+    }
+
+    /// Returns whether both handles refer to the same element.
+    #[cfg(feature = "mcp")]
+    pub(crate) fn is_same_element(&self, other: &Self) -> bool {
+        self.item == other.item && self.element_index == other.element_index
+    }
+
     fn window_adapter(&self) -> Option<Rc<dyn i_slint_core::window::WindowAdapter>> {
         self.item.upgrade().and_then(|item| item.window_adapter())
     }
@@ -1048,6 +1147,39 @@ pub async fn drag(&self, target: LogicalPosition, button: PointerEventButton) {
         window.dispatch_event(WindowEvent::PointerReleased { position: target, button });
     }
 
+    /// Like [Self::drag()], but with exactly `steps` interpolated `PointerMoved` events (at
+    /// least one, the last one at `target`) between press and release, regardless of the
+    /// distance.
+    #[cfg(feature = "mcp")]
+    pub(crate) async fn drag_in_steps(
+        &self,
+        target: LogicalPosition,
+        button: PointerEventButton,
+        steps: usize,
+    ) {
+        let Some(window_adapter) = self.window_adapter() else {
+            return;
+        };
+        let window = window_adapter.window();
+        let start = self.absolute_center();
+
+        window.dispatch_event(WindowEvent::PointerMoved { position: start });
+        window.dispatch_event(WindowEvent::PointerPressed { position: start, button });
+
+        let steps = steps.max(1);
+        for i in 1..=steps {
+            let t = i as f32 / steps as f32;
+            let position = LogicalPosition::new(
+                start.x + (target.x - start.x) * t,
+                start.y + (target.y - start.y) * t,
+            );
+            wait_for(Duration::from_millis(DRAG_STEP_DELAY_MS)).await;
+            window.dispatch_event(WindowEvent::PointerMoved { position });
+        }
+
+        window.dispatch_event(WindowEvent::PointerReleased { position: target, button });
+    }
+
     /// Simulates a drag gesture from the element's center to the given target position.
     ///
     /// Compared to [Self::drag()], this function uses mock time instead
@@ -1076,6 +1208,17 @@ pub fn scroll(&self, delta_x: f32, delta_y: f32) {
         window.dispatch_event(WindowEvent::PointerScrolled { position: center, delta_x, delta_y });
     }
 
+    /// Moves the mouse pointer to the element's center without pressing any button, so that
+    /// hover effects (such as `TouchArea`'s `has-hover` or tooltips) take effect.
+    #[cfg(feature = "mcp")]
+    pub(crate) fn hover(&self) {
+        let Some(window_adapter) = self.window_adapter() else {
+            return;
+        };
+        let position = self.absolute_center();
+        window_adapter.window().dispatch_event(WindowEvent::PointerMoved { position });
+    }
+
     fn active_popups(&self) -> Vec<(ItemRc, ItemTreeRc)> {
         self.item
             .upgrade()
@@ -1094,7 +1237,7 @@ fn active_popups(&self) -> Vec<(ItemRc, ItemTreeRc)> {
     }
 }
 
-async fn wait_for(duration: std::time::Duration) {
+pub(crate) async fn wait_for(duration: std::time::Duration) {
     enum AsyncTimerState {
         Starting,
         Waiting(std::task::Waker),
@@ -1308,6 +1451,100 @@ fn test_normalize_id() {
     assert_eq!(root.query_descendants().match_id("App::the_element").find_all().len(), 1);
 }
 
+#[cfg(feature = "mcp")]
+#[test]
+fn test_hover() {
+    crate::init_no_event_loop();
+
+    slint::slint! {
+        export component App inherits Window {
+            width: 100px;
+            height: 100px;
+            out property <bool> hovered: ta.has-hover;
+            ta := TouchArea {
+                x: 50px;
+                width: 50px;
+            }
+            other := Rectangle {
+                x: 0px;
+                width: 50px;
+            }
+        }
+    }
+
+    let app = App::new().unwrap();
+    let root = app.root_element();
+    let find = |id| root.query_descendants().match_id(id).find_first().unwrap();
+
+    assert!(!app.get_hovered());
+    find("App::ta").hover();
+    assert!(app.get_hovered());
+    find("App::other").hover();
+    assert!(!app.get_hovered());
+}
+
+#[test]
+fn test_supported_accessibility_actions() {
+    crate::init_no_event_loop();
+
+    slint::slint! {
+        export component App inherits Window {
+            spin := Rectangle {
+                accessible-role: spinbox;
+                accessible-action-increment => {}
+                accessible-action-decrement => {}
+            }
+            plain := Rectangle {
+                accessible-role: text;
+            }
+        }
+    }
+
+    let app = App::new().unwrap();
+    let root = app.root_element();
+    let find = |id| root.query_descendants().match_id(id).find_first().unwrap();
+
+    assert_eq!(
+        find("App::spin").supported_accessibility_actions(),
+        SupportedAccessibilityAction::Increment | SupportedAccessibilityAction::Decrement
+    );
+    assert!(find("App::plain").supported_accessibility_actions().is_empty());
+}
+
+#[test]
+fn test_sibling_index_and_z_order() {
+    crate::init_no_event_loop();
+
+    slint::slint! {
+        export component App inherits Window {
+            first := Rectangle {
+                nested := Rectangle {}
+            }
+            raised := Rectangle {
+                z: 1;
+            }
+            last := Rectangle {}
+        }
+    }
+
+    let app = App::new().unwrap();
+    let root = app.root_element();
+    let find = |id| root.query_descendants().match_id(id).find_first().unwrap();
+
+    assert_eq!(root.sibling_index(), None);
+    assert_eq!(root.z_order(), Some(0));
+    assert_eq!(find("App::first").sibling_index(), Some(0));
+    assert_eq!(find("App::nested").sibling_index(), Some(0));
+    // `z: 1` moves the rectangle behind its siblings in the item tree, so it's painted last.
+    assert_eq!(find("App::last").sibling_index(), Some(1));
+    assert_eq!(find("App::raised").sibling_index(), Some(2));
+
+    let z = |id| find(id).z_order().unwrap();
+    assert!(z("App::first") < z("App::nested"));
+    assert!(z("App::nested") < z("App::last"));
+    assert!(z("App::last") < z("App::raised"));
+}
+
 #[test]
 fn test_opacity() {
     crate::init_no_event_loop();
@@ -18,6 +18,9 @@ struct TestingClient {
     state: Rc<IntrospectionState>,
     message_loop_future: std::cell::OnceCell<i_slint_core::future::JoinHandle<()>>,
     server_addr: String,
+    /// How long to keep trying to reconnect after the connection to the test server is
+    /// lost. `None` (the default) means the connection is not re-established.
+    reconnect_timeout: Option<std::time::Duration>,
 }
 
 impl TestingClient {
@@ -26,7 +29,24 @@ fn new(state: Rc<IntrospectionState>) -> Option<Rc<Self>> {
             return None;
         };
 
-        Some(Rc::new(Self { state, message_loop_future: Default::default(), server_addr }))
+        let reconnect_timeout = std::env::var("SLINT_TEST_SERVER_RECONNECT_TIMEOUT_MS")
+            .ok()
+            .and_then(|value| match value.parse::<u64>() {
+                Ok(ms) => Some(std::time::Duration::from_millis(ms)),
+                Err(_) => {
+                    eprintln!(
+                        "Ignoring invalid SLINT_TEST_SERVER_RECONNECT_TIMEOUT_MS value '{value}'"
+                    );
+                    None
+                }
+            });
+
+        Some(Rc::new(Self {
+            state,
+            message_loop_future: Default::default(),
+            server_addr,
+            reconnect_timeout,
+        }))
     }
 
     fn start_if_needed(self: &Rc<Self>) {
@@ -38,7 +58,7 @@ fn start_if_needed(self: &Rc<Self>) {
                     let this = this.clone();
                     context
                         .spawn_local(async move {
-                            message_loop(&this.server_addr, |request| {
+                            message_loop(&this.server_addr, this.reconnect_timeout, |request| {
                                 let this = this.clone();
                                 Box::pin(async move { this.handle_request(request).await })
                             })
@@ -71,6 +91,8 @@ async fn handle_request(
             Req::RequestFindElementsById(proto::RequestFindElementsById {
                 window_handle,
                 elements_id,
+                offset,
+                limit,
             }) => {
                 let window_index = handle_to_index(window_handle.ok_or_else(|| {
                     "find elements by id request missing window handle".to_string()
@@ -79,6 +101,8 @@ async fn handle_request(
                     &self.state,
                     window_index,
                     &elements_id,
+                    offset,
+                    limit,
                 )?)
             }
             Req::RequestElementProperties(proto::RequestElementProperties { element_handle }) => {
@@ -115,15 +139,20 @@ async fn handle_request(
             Req::RequestTakeSnapshot(proto::RequestTakeSnapshot {
                 window_handle,
                 image_mime_type,
+                element_handle,
+                quality,
             }) => {
                 let window_index = handle_to_index(
                     window_handle
                         .ok_or_else(|| "grab window request missing window handle".to_string())?,
                 )?;
+                let crop_to = element_handle.map(handle_to_index).transpose()?;
                 Resp::TakeSnapshotResponse(dispatch::take_snapshot(
                     &self.state,
                     window_index,
                     &image_mime_type,
+                    crop_to,
+                    introspection::jpeg_quality(quality),
                 )?)
             }
             Req::RequestElementClick(proto::RequestElementClick {
@@ -177,6 +206,8 @@ async fn handle_request(
                 element_handle,
                 query_stack,
                 find_all,
+                offset,
+                limit,
             }) => {
                 let element_index = handle_to_index(element_handle.ok_or_else(|| {
                     "run element query request missing element handle".to_string()
@@ -186,6 +217,8 @@ async fn handle_request(
                     element_index,
                     query_stack,
                     find_all,
+                    offset,
+                    limit,
                 )?)
             }
             Req::RequestEventLog(proto::RequestEventLog {
@@ -246,6 +279,7 @@ pub fn init() -> Result<(), EventLoopError> {
 
 async fn message_loop(
     server_addr: &str,
+    reconnect_timeout: Option<std::time::Duration>,
     mut message_callback: impl FnMut(
         Option<proto::request_to_aut::Msg>,
     ) -> std::pin::Pin<
@@ -254,37 +288,101 @@ async fn message_loop(
 ) {
     debug_log!("Attempting to connect to testing server at {server_addr}");
 
-    let mut stream = match async_net::TcpStream::connect(server_addr).await {
+    let mut stream = match connect(server_addr).await {
         Ok(stream) => stream,
         Err(err) => {
             eprintln!("Error connecting to Slint test server at {server_addr}: {}", err);
             return;
         }
     };
+    debug_log!("Connected to test server");
+
+    loop {
+        let err_msg = serve_connection(&mut stream, &mut message_callback).await;
+        eprintln!("{}, closing connection to test server", err_msg);
+
+        // Close connection explicitly to notify the server if it is still connected.
+        stream.shutdown(std::net::Shutdown::Both).ok();
+
+        let Some(reconnect_timeout) = reconnect_timeout else {
+            return;
+        };
+        match reconnect(server_addr, reconnect_timeout).await {
+            Some(new_stream) => stream = new_stream,
+            None => {
+                eprintln!(
+                    "Unable to reconnect to Slint test server at {server_addr} within {}ms, giving up. Restart the application to connect again.",
+                    reconnect_timeout.as_millis()
+                );
+                return;
+            }
+        }
+    }
+}
+
+async fn connect(server_addr: &str) -> std::io::Result<async_net::TcpStream> {
+    let stream = async_net::TcpStream::connect(server_addr).await?;
     // Attempt to disable the Nagle algorithm to favor faster packet exchange (latency)
     // over throughput.
     stream.set_nodelay(true).ok();
-    debug_log!("Connected to test server");
+    Ok(stream)
+}
 
+/// Tries to connect to the test server again, backing off exponentially between attempts
+/// (100ms, doubling up to 2s), until `timeout` has elapsed.
+async fn reconnect(
+    server_addr: &str,
+    timeout: std::time::Duration,
+) -> Option<async_net::TcpStream> {
+    let deadline = std::time::Instant::now() + timeout;
+    let mut delay = std::time::Duration::from_millis(100);
+    for attempt in 1.. {
+        eprintln!("Reconnecting to Slint test server at {server_addr} (attempt {attempt})");
+        match connect(server_addr).await {
+            Ok(stream) => {
+                eprintln!("Reconnected to Slint test server at {server_addr}");
+                return Some(stream);
+            }
+            Err(err) => eprintln!("Reconnect attempt {attempt} failed: {err}"),
+        }
+        let remaining = deadline.saturating_duration_since(std::time::Instant::now());
+        if remaining.is_zero() {
+            break;
+        }
+        crate::search_api::wait_for(delay.min(remaining)).await;
+        delay = (delay * 2).min(std::time::Duration::from_secs(2));
+    }
+    None
+}
+
+/// Answers requests on `stream` until the connection fails, returning the reason.
+async fn serve_connection(
+    stream: &mut async_net::TcpStream,
+    message_callback: &mut impl FnMut(
+        Option<proto::request_to_aut::Msg>,
+    ) -> std::pin::Pin<
+        Box<dyn std::future::Future<Output = Result<proto::aut_response::Msg, String>>>,
+    >,
+) -> &'static str {
     // Note: Handling communication errors gracefully (without panic) to avoid
     // triggering any crash reporter from the OS.
-    let err_msg = loop {
+    loop {
         let mut message_size_buf = vec![0; 4];
         if stream.read_exact(&mut message_size_buf).await.is_err() {
-            break "Unable to read request header from AUT connection";
+            return "Unable to read request header from AUT connection";
         }
 
         let message_size: usize =
             Cursor::new(message_size_buf).read_u32::<BigEndian>().unwrap() as usize;
         let mut message_buf = vec![0; message_size];
         if stream.read_exact(&mut message_buf).await.is_err() {
-            break "Unable to read request data from AUT connection";
+            return "Unable to read request data from AUT connection";
         }
 
         let message = match proto::RequestToAut::decode(&message_buf[..]) {
             Ok(msg) => msg,
             Err(_) => {
-                break "Error de-serializing AUT request message";
+                return "Error de-serializing AUT request message";
             }
         };
         let response = message_callback(message.msg).await.unwrap_or_else(|message| {
@@ -295,13 +393,9 @@ async fn message_loop(
         binary_message.write_u32::<BigEndian>(response.encoded_len() as u32).unwrap();
         response.encode(&mut binary_message).unwrap();
         if stream.write_all(&binary_message).await.is_err() {
-            break "Unable to write AUT response body";
+            return "Unable to write AUT response body";
         }
-    };
-    eprintln!("{}, closing connection to test server", err_msg);
-
-    // Close connection explicitly to notify the server if it is still connected.
-    stream.shutdown(std::net::Shutdown::Both).ok();
+    }
 }
 
 fn convert_logical_position(pos: proto::LogicalPosition) -> i_slint_core::api::LogicalPosition {
@@ -1,11 +1,17 @@
 // Copyright © SixtyFPS GmbH <info@slint.dev>
 // SPDX-License-Identifier: GPL-3.0-only OR LicenseRef-Slint-Royalty-free-2.0 OR LicenseRef-Slint-Software-3.0
 
-//! UTF-16 ↔ UTF-8 offset conversion utilities.
+//! Text offset conversion and text boundary utilities.
 //!
 //! Slint uses UTF-8 byte offsets internally. Platform protocols and language
-//! servers often use UTF-16 code unit offsets. This module converts between
-//! the two without allocating.
+//! servers often use UTF-16 code unit offsets, and some FFI consumers count
+//! Unicode scalar values (UTF-32). This module converts between these without
+//! allocating, and finds grapheme cluster and word boundaries for cursor
+//! movement.
+
+use alloc::vec;
+use alloc::vec::Vec;
+use unicode_segmentation::UnicodeSegmentation;
 
 /// Converts a UTF-8 byte offset to a UTF-16 code unit offset.
 ///
@@ -19,6 +25,88 @@ pub fn byte_offset_to_utf16_offset(text: &str, byte_offset: usize) -> usize {
     text[..byte_offset.min(text.len())].chars().map(|c| c.len_utf16()).sum()
 }
 
+/// Returns the length of `text` in UTF-16 code units.
+///
+/// This is at most `text.len()` (the length in UTF-8 bytes) and at least
+/// `text.chars().count()`, as characters outside the Basic Multilingual Plane
+/// take two code units.
+pub fn utf16_len(text: &str) -> usize {
+    text.chars().map(|c| c.len_utf16()).sum()
+}
+
+/// Returns an iterator over the `(byte_offset, utf16_offset)` pair at every
+/// character boundary of `text`, in order, ending with
+/// `(text.len(), utf16_len(text))`.
+pub fn offset_pairs(text: &str) -> impl Iterator<Item = (usize, usize)> + '_ {
+    // The sentinel char only marks the end position; its length is never used.
+    text.char_indices().chain(core::iter::once((text.len(), '\0'))).scan(
+        0,
+        |utf16_offset, (byte_offset, c)| {
+            let pair = (byte_offset, *utf16_offset);
+            *utf16_offset += c.len_utf16();
+            Some(pair)
+        },
+    )
+}
+
+/// Returns the byte length of the longest prefix of `text` that is at most
+/// `max_units` UTF-16 code units long, for fields with a length limit.
+///
+/// A surrogate pair is never split: if the limit falls inside one, the prefix
+/// ends before that character. The result is always a character boundary, so
+/// `&text[..truncate_to_utf16_units(text, max_units)]` is valid.
+pub fn truncate_to_utf16_units(text: &str, max_units: usize) -> usize {
+    offset_pairs(text)
+        .take_while(|(_, utf16_offset)| *utf16_offset <= max_units)
+        .last()
+        .map_or(0, |(byte_offset, _)| byte_offset)
+}
+
+/// Converts several UTF-8 byte offsets to UTF-16 code unit offsets, walking
+/// `text` only once.
+///
+/// The offsets may be given in any order; the result has one entry per input
+/// offset, in the same order. As with [`byte_offset_to_utf16_offset`], each
+/// offset must lie on a valid UTF-8 character boundary within `text`.
+pub fn byte_offsets_to_utf16_offsets(text: &str, byte_offsets: &[usize]) -> Vec<usize> {
+    let mut order: Vec<usize> = (0..byte_offsets.len()).collect();
+    order.sort_unstable_by_key(|&i| byte_offsets[i]);
+    let mut result = vec![0; byte_offsets.len()];
+    let mut chars = text.char_indices().peekable();
+    let mut utf16_offset = 0;
+    for i in order {
+        let byte_offset = byte_offsets[i];
+        debug_assert!(
+            text.is_char_boundary(byte_offset),
+            "byte_offset {byte_offset} is not on a UTF-8 character boundary"
+        );
+        while let Some((_, c)) = chars.next_if(|(idx, _)| *idx < byte_offset) {
+            utf16_offset += c.len_utf16();
+        }
+        result[i] = utf16_offset;
+    }
+    result
+}
+
+/// Converts a UTF-8 byte offset to a UTF-16 code unit offset, or returns `None`
+/// if `byte_offset` is beyond the end of `text` or not on a character boundary.
+///
+/// Use this instead of [`byte_offset_to_utf16_offset`] for offsets that come
+/// from untrusted input, such as input method events.
+pub fn byte_offset_to_utf16_offset_checked(text: &str, byte_offset: usize) -> Option<usize> {
+    text.is_char_boundary(byte_offset)
+        .then(|| text[..byte_offset].chars().map(|c| c.len_utf16()).sum())
+}
+
+/// Converts a UTF-8 byte offset to a UTF-16 code unit offset without panicking.
+///
+/// If the offset falls in the middle of a character, it is floored to the
+/// start of that character; beyond the end of the string, it is clamped to
+/// `text.len()`.
+pub fn byte_offset_to_utf16_offset_clamped(text: &str, byte_offset: usize) -> usize {
+    byte_offset_to_utf16_offset(text, text.floor_char_boundary(byte_offset.min(text.len())))
+}
+
 /// Converts a UTF-16 code unit offset to a UTF-8 byte offset.
 ///
 /// If the offset falls in the middle of a surrogate pair or beyond the end of
@@ -34,6 +122,125 @@ pub fn utf16_offset_to_byte_offset_clamped(text: &str, utf16_offset: usize) -> u
     text.len()
 }
 
+/// Converts a zero-based line and UTF-16 column to a UTF-8 byte offset.
+///
+/// Lines are separated by `\n`. A `\r` directly before the `\n` belongs to the
+/// line terminator, so columns never address it. Out-of-range positions are
+/// clamped: a column past the end of its line maps to the end of the line
+/// content, a column inside a surrogate pair to the end of that character, and
+/// a line past the last one to `text.len()`.
+pub fn line_col_to_byte_offset(text: &str, line: usize, col: usize) -> usize {
+    let mut line_start = 0;
+    for (index, content) in text.split('\n').enumerate() {
+        let line_end = line_start + content.len();
+        if index == line {
+            let content = if line_end < text.len() {
+                content.strip_suffix('\r').unwrap_or(content)
+            } else {
+                content
+            };
+            return line_start + utf16_offset_to_byte_offset_clamped(content, col);
+        }
+        line_start = line_end + 1;
+    }
+    text.len()
+}
+
+/// Converts a UTF-8 byte offset to a zero-based line and UTF-16 column.
+///
+/// This is the inverse of [`line_col_to_byte_offset`]. An offset between the
+/// `\r` and `\n` of a `\r\n` terminator reports the end of the line content.
+/// Offsets beyond the end of the string are clamped to `text.len()`; otherwise
+/// `byte_offset` must lie on a valid UTF-8 character boundary.
+pub fn byte_offset_to_line_col(text: &str, byte_offset: usize) -> (usize, usize) {
+    let byte_offset = byte_offset.min(text.len());
+    let before = &text[..byte_offset];
+    let line_start = before.rfind('\n').map_or(0, |idx| idx + 1);
+    let line = before[..line_start].matches('\n').count();
+    let mut content = &before[line_start..];
+    if text[byte_offset..].starts_with('\n') {
+        content = content.strip_suffix('\r').unwrap_or(content);
+    }
+    (line, utf16_len(content))
+}
+
+/// Converts a UTF-8 byte offset to a UTF-32 offset, i.e. the number of Unicode
+/// scalar values (`char`s) before it.
+///
+/// `byte_offset` must lie on a valid UTF-8 character boundary within `text`.
+/// In debug builds an assertion fires for invalid offsets.
+pub fn byte_offset_to_utf32_offset(text: &str, byte_offset: usize) -> usize {
+    debug_assert!(
+        text.is_char_boundary(byte_offset),
+        "byte_offset {byte_offset} is not on a UTF-8 character boundary"
+    );
+    text[..byte_offset.min(text.len())].chars().count()
+}
+
+/// Converts a UTF-32 offset (a count of Unicode scalar values) to a UTF-8 byte offset.
+///
+/// If the offset is beyond the end of the string, it is clamped to `text.len()`.
+pub fn utf32_offset_to_byte_offset_clamped(text: &str, utf32_offset: usize) -> usize {
+    text.char_indices().nth(utf32_offset).map_or(text.len(), |(idx, _)| idx)
+}
+
+/// Returns the byte offset of the first grapheme cluster boundary after `offset`.
+///
+/// If `offset` lies inside a grapheme cluster, this is the end of that cluster.
+/// At or beyond the end of the string, `text.len()` is returned.
+pub fn next_grapheme_boundary(text: &str, offset: usize) -> usize {
+    text.grapheme_indices(true).map(|(idx, _)| idx).find(|idx| *idx > offset).unwrap_or(text.len())
+}
+
+/// Returns the byte offset of the last grapheme cluster boundary before `offset`.
+///
+/// If `offset` lies inside a grapheme cluster, this is the start of that cluster.
+/// At the start of the string, `0` is returned; offsets beyond the end are
+/// treated as `text.len()`.
+pub fn prev_grapheme_boundary(text: &str, offset: usize) -> usize {
+    let offset = offset.min(text.len());
+    text.grapheme_indices(true)
+        .map(|(idx, _)| idx)
+        .take_while(|idx| *idx < offset)
+        .last()
+        .unwrap_or(0)
+}
+
+fn is_whitespace_segment(segment: &str) -> bool {
+    segment.chars().all(char::is_whitespace)
+}
+
+/// Returns the byte offset at the end of the next word after `offset`, for
+/// moving a text cursor one word forward (Ctrl+Right).
+///
+/// Words are the segments between Unicode word boundaries (UAX #29); each
+/// punctuation character and, in scripts without spaces such as CJK, each
+/// ideograph counts as a word of its own. Whitespace between `offset` and the
+/// next word is skipped, so the result is the end of the first word that ends
+/// after `offset`. If `offset` lies inside a word, that is the end of the same
+/// word. Returns `text.len()` if no word follows.
+pub fn next_word_boundary(text: &str, offset: usize) -> usize {
+    text.split_word_bound_indices()
+        .skip_while(|(idx, segment)| idx + segment.len() <= offset)
+        .find(|(_, segment)| !is_whitespace_segment(segment))
+        .map_or(text.len(), |(idx, segment)| idx + segment.len())
+}
+
+/// Returns the byte offset at the start of the previous word before `offset`,
+/// for moving a text cursor one word backward (Ctrl+Left).
+///
+/// This mirrors [`next_word_boundary`]: whitespace immediately before `offset`
+/// is skipped, and the result is the start of the first word that starts
+/// before `offset`. If `offset` lies inside a word, that is the start of the
+/// same word. Returns `0` if no word precedes.
+pub fn prev_word_boundary(text: &str, offset: usize) -> usize {
+    text.split_word_bound_indices()
+        .rev()
+        .skip_while(|(idx, _)| *idx >= offset)
+        .find(|(_, segment)| !is_whitespace_segment(segment))
+        .map_or(0, |(idx, _)| idx)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -63,6 +270,120 @@ fn test_byte_to_utf16() {
         }
     }
 
+    #[test]
+    fn test_utf16_len() {
+        let cases: &[(&str, usize)] = &[
+            ("", 0),
+            ("hello", 5),  // ASCII: one unit per byte
+            ("日本語", 3), // BMP: one unit per 3-byte character
+            ("a😀b", 4),   // emoji: surrogate pair
+            ("👨\u{200d}👩\u{200d}👧", 8),
+        ];
+        for &(text, expected) in cases {
+            assert_eq!(utf16_len(text), expected, "utf16_len({text:?})");
+            assert_eq!(utf16_len(text), byte_offset_to_utf16_offset(text, text.len()));
+        }
+    }
+
+    #[test]
+    fn test_offset_pairs() {
+        let text = "héllo 日本語 😀 world"; // cspell:disable-line
+        let boundaries = text.char_indices().map(|(idx, _)| idx).chain([text.len()]);
+        assert!(
+            offset_pairs(text).eq(boundaries
+                .map(|byte_offset| (byte_offset, byte_offset_to_utf16_offset(text, byte_offset))))
+        );
+        assert!(offset_pairs("a😀").eq([(0, 0), (1, 1), (5, 3)]));
+        assert!(offset_pairs("").eq([(0, 0)]));
+    }
+
+    #[test]
+    fn test_truncate_to_utf16_units() {
+        let cases: &[(&str, usize, usize)] = &[
+            ("", 0, 0),
+            ("", 3, 0),
+            ("hello", 0, 0),
+            ("hello", 3, 3),
+            ("hello", 10, 5), // limit beyond the text
+            ("日本語", 2, 6),
+            ("a😀b", 1, 1), // just before the surrogate pair
+            ("a😀b", 2, 1), // inside the surrogate pair → stop before it
+            ("a😀b", 3, 5), // exactly at the end of the pair
+            ("a😀b", 4, 6),
+        ];
+        for &(text, max_units, expected) in cases {
+            assert_eq!(
+                truncate_to_utf16_units(text, max_units),
+                expected,
+                "truncate_to_utf16_units({text:?}, {max_units})"
+            );
+            assert!(utf16_len(&text[..expected]) <= max_units);
+        }
+    }
+
+    #[test]
+    fn test_byte_to_utf16_batch() {
+        let text = "héllo 日本語 😀 world"; // cspell:disable-line
+        let mut offsets: Vec<usize> = text.char_indices().map(|(idx, _)| idx).collect();
+        offsets.push(text.len());
+        // Unsorted and with duplicates, like the cursors of a multi-cursor edit.
+        offsets.reverse();
+        offsets.extend([4, 0, 4]);
+        let expected: Vec<usize> =
+            offsets.iter().map(|&offset| byte_offset_to_utf16_offset(text, offset)).collect();
+        assert_eq!(byte_offsets_to_utf16_offsets(text, &offsets), expected);
+        assert_eq!(byte_offsets_to_utf16_offsets(text, &[]), Vec::<usize>::new());
+        assert_eq!(byte_offsets_to_utf16_offsets("", &[0, 0]), [0, 0]);
+    }
+
+    #[test]
+    fn test_byte_to_utf16_checked() {
+        let cases: &[(&str, usize, Option<usize>)] = &[
+            ("hello", 3, Some(3)),
+            ("hello", 5, Some(5)),
+            ("hello", 6, None), // beyond end
+            ("", 0, Some(0)),
+            ("", 1, None),
+            ("日本語", 3, Some(1)),
+            ("日本語", 1, None), // inside a 3-byte character
+            ("日本語", 4, None),
+            ("a😀b", 5, Some(3)),
+            ("a😀b", 2, None), // inside the emoji
+            ("a😀b", 4, None),
+        ];
+        for &(text, byte_col, expected) in cases {
+            assert_eq!(
+                byte_offset_to_utf16_offset_checked(text, byte_col),
+                expected,
+                "byte_offset_to_utf16_offset_checked({text:?}, {byte_col})"
+            );
+        }
+    }
+
+    #[test]
+    fn test_byte_to_utf16_clamped() {
+        let cases: &[(&str, usize, usize)] = &[
+            ("hello", 3, 3),
+            ("hello", 100, 5), // beyond end → clamped to text.len()
+            ("", 0, 0),
+            ("", 5, 0),
+            ("日本語", 3, 1),
+            ("日本語", 4, 1), // mid-character → floored to its start
+            ("日本語", 8, 2),
+            ("a😀b", 1, 1),
+            ("a😀b", 3, 1), // inside the emoji
+            ("a😀b", 5, 3),
+            ("a😀b", 7, 4),
+        ];
+        for &(text, byte_col, expected) in cases {
+            assert_eq!(
+                byte_offset_to_utf16_offset_clamped(text, byte_col),
+                expected,
+                "byte_offset_to_utf16_offset_clamped({text:?}, {byte_col})"
+            );
+        }
+    }
+
     #[test]
     fn test_utf16_to_byte_clamped() {
         let cases: &[(&str, usize, usize)] = &[
@@ -91,6 +412,88 @@ fn test_utf16_to_byte_clamped() {
         }
     }
 
+    #[test]
+    fn test_line_col_conversions() {
+        // Lines: "ab" at 0, "日本" at 3 (ends in \r\n), "😀x" at 11, and an empty last line at 17.
+        let text = "ab\n日本\r\n😀x\n";
+        let to_offset: &[(usize, usize, usize)] = &[
+            (0, 0, 0),
+            (0, 2, 2),
+            (0, 5, 2), // column past the end of the line → clamped
+            (1, 0, 3),
+            (1, 1, 6),
+            (1, 2, 9),
+            (1, 3, 9), // never addresses the \r
+            (2, 0, 11),
+            (2, 1, 15), // mid-surrogate → clamp past emoji
+            (2, 2, 15),
+            (2, 3, 16),
+            (3, 0, 17),
+            (4, 0, 17), // line past the end → clamped to text.len()
+        ];
+        for &(line, col, expected) in to_offset {
+            assert_eq!(
+                line_col_to_byte_offset(text, line, col),
+                expected,
+                "line_col_to_byte_offset({text:?}, {line}, {col})"
+            );
+        }
+        let to_line_col: &[(usize, (usize, usize))] = &[
+            (0, (0, 0)),
+            (2, (0, 2)),
+            (3, (1, 0)),
+            (6, (1, 1)),
+            (9, (1, 2)),
+            (10, (1, 2)), // between \r and \n
+            (11, (2, 0)),
+            (15, (2, 2)),
+            (16, (2, 3)),
+            (17, (3, 0)),
+            (100, (3, 0)), // beyond end → clamped
+        ];
+        for &(offset, expected) in to_line_col {
+            assert_eq!(
+                byte_offset_to_line_col(text, offset),
+                expected,
+                "byte_offset_to_line_col({text:?}, {offset})"
+            );
+        }
+        assert_eq!(line_col_to_byte_offset("", 0, 3), 0);
+        assert_eq!(byte_offset_to_line_col("", 0), (0, 0));
+        // A trailing \r without \n is line content.
+        assert_eq!(line_col_to_byte_offset("a\r", 0, 2), 2);
+        assert_eq!(byte_offset_to_line_col("a\r", 2), (0, 2));
+    }
+
+    #[test]
+    fn test_utf32_conversions() {
+        let cases: &[(&str, usize, usize)] = &[
+            ("hello", 0, 0),
+            ("hello", 3, 3), // ASCII: byte == scalar value
+            ("hello", 5, 5),
+            ("", 0, 0),
+            ("日本語", 3, 1), // BMP: 3 bytes → 1 scalar value
+            ("日本語", 9, 3),
+            ("a😀b", 1, 1),
+            ("a😀b", 5, 2), // supplementary plane: 4 bytes → 1 scalar value
+            ("a😀b", 6, 3),
+        ];
+        for &(text, byte_col, utf32_col) in cases {
+            assert_eq!(
+                byte_offset_to_utf32_offset(text, byte_col),
+                utf32_col,
+                "byte_offset_to_utf32_offset({text:?}, {byte_col})"
+            );
+            assert_eq!(
+                utf32_offset_to_byte_offset_clamped(text, utf32_col),
+                byte_col,
+                "utf32_offset_to_byte_offset_clamped({text:?}, {utf32_col})"
+            );
+        }
+        assert_eq!(utf32_offset_to_byte_offset_clamped("a😀b", 100), 6); // beyond end → clamped
+        assert_eq!(utf32_offset_to_byte_offset_clamped("", 1), 0);
+    }
+
     #[test]
     fn test_roundtrip() {
         let text = "héllo 日本語 😀 world"; // cspell:disable-line
@@ -100,5 +503,92 @@ fn test_roundtrip() {
         }
         let utf16 = byte_offset_to_utf16_offset(text, text.len());
         assert_eq!(utf16_offset_to_byte_offset_clamped(text, utf16), text.len());
+        for byte_idx in text.char_indices().map(|(idx, _)| idx).chain([text.len()]) {
+            let utf32 = byte_offset_to_utf32_offset(text, byte_idx);
+            assert_eq!(utf32_offset_to_byte_offset_clamped(text, utf32), byte_idx);
+        }
+    }
+
+    #[test]
+    fn test_grapheme_boundaries() {
+        let family = "👨\u{200d}👩\u{200d}👧"; // 18 bytes, one cluster
+        let flag = "🇩🇪"; // two regional indicators, 8 bytes
+        let combining = "e\u{301}"; // e + combining acute accent, 3 bytes
+        let cases: &[(&str, usize, usize, usize)] = &[
+            // (text, offset, next, prev)
+            ("abc", 0, 1, 0),
+            ("abc", 1, 2, 0),
+            ("abc", 3, 3, 2),
+            ("abc", 100, 3, 2), // beyond end → clamped
+            ("", 0, 0, 0),
+            (family, 0, 18, 0),
+            (family, 4, 18, 0), // inside the cluster
+            (family, 18, 18, 0),
+            (flag, 0, 8, 0),
+            (flag, 4, 8, 0), // between the two regional indicators
+            (flag, 8, 8, 0),
+            (combining, 0, 3, 0),
+            (combining, 1, 3, 0), // before the combining mark
+            (combining, 3, 3, 0),
+        ];
+        for &(text, offset, next, prev) in cases {
+            assert_eq!(
+                next_grapheme_boundary(text, offset),
+                next,
+                "next_grapheme_boundary({text:?}, {offset})"
+            );
+            assert_eq!(
+                prev_grapheme_boundary(text, offset),
+                prev,
+                "prev_grapheme_boundary({text:?}, {offset})"
+            );
+        }
+
+        // Stepping through mixed text visits every cluster boundary in both directions.
+        let text = "a👨\u{200d}👩\u{200d}👧b🇩🇪e\u{301}";
+        let boundaries = [0, 1, 19, 20, 28, 31];
+        for pair in boundaries.windows(2) {
+            assert_eq!(next_grapheme_boundary(text, pair[0]), pair[1]);
+            assert_eq!(prev_grapheme_boundary(text, pair[1]), pair[0]);
+        }
+    }
+
+    #[test]
+    fn test_word_boundaries() {
+        let cases: &[(&str, usize, usize, usize)] = &[
+            // (text, offset, next, prev)
+            ("hello world", 0, 5, 0),
+            ("hello world", 2, 5, 0),  // inside a word
+            ("hello world", 5, 11, 0), // whitespace is skipped
+            ("hello world", 6, 11, 0),
+            ("hello world", 11, 11, 6),
+            ("hello world", 100, 11, 6), // beyond end → clamped
+            ("  padded  ", 0, 8, 0),
+            ("  padded  ", 10, 10, 2),
+            ("", 0, 0, 0),
+            ("foo, bar", 3, 4, 0), // punctuation is a word of its own
+            ("foo, bar", 4, 8, 3),
+            ("foo, bar", 5, 8, 3),
+            ("can't stop", 0, 5, 0), // apostrophes don't split words
+            ("日本語", 0, 3, 0),     // each ideograph is a word
+            ("日本語", 3, 6, 0),
+            ("日本語", 9, 9, 6),
+            ("abc日本", 0, 3, 0), // script changes are boundaries
+            ("abc日本", 6, 9, 3),
+            ("Слава мир", 0, 10, 0),   // cspell:disable-line
+            ("Слава мир", 17, 17, 11), // cspell:disable-line
+        ];
+        for &(text, offset, next, prev) in cases {
+            assert_eq!(
+                next_word_boundary(text, offset),
+                next,
+                "next_word_boundary({text:?}, {offset})"
+            );
+            assert_eq!(
+                prev_word_boundary(text, offset),
+                prev,
+                "prev_word_boundary({text:?}, {offset})"
+            );
+        }
     }
 }